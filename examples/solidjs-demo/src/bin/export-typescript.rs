@@ -0,0 +1,6 @@
+//! Writes the TypeScript client into `ui/src`, run by `pnpm dev` and
+//! `pnpm build` so the frontend types always match the commands.
+
+fn main() -> rust_webui::Result<()> {
+    solidjs_demo::api().export_typescript(concat!(env!("CARGO_MANIFEST_DIR"), "/ui/src"))
+}
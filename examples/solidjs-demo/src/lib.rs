@@ -0,0 +1,19 @@
+//! The commands the UI calls, shared by the app and the TypeScript export.
+
+use rust_webui::{command, json::CommandError, ts::Api, ts::TsType};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, TsType)]
+pub struct Add2Input {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[command]
+pub fn add2(input: Add2Input) -> Result<f64, CommandError> {
+    Ok(input.x + input.y)
+}
+
+pub fn api() -> Api {
+    Api::new().command::<add2>()
+}
@@ -1,5 +1,5 @@
 import { createSignal, type Component, createEffect } from 'solid-js';
-import { webuiCall } from './utils';
+import { add2 } from './webui-client';
 
 const App: Component = () => {
   const [x, setX] = createSignal(0);
@@ -8,7 +8,7 @@ const App: Component = () => {
   const [sum, setSum] = createSignal(0);
 
   createEffect(() => {
-    webuiCall<number>("add2", { x: x(), y: y() }).then((res) => {
+    add2({ x: x(), y: y() }).then((res) => {
       setSum(res);
     });
   })
@@ -5,6 +5,7 @@ type DataTypes = string | number | boolean | Uint8Array;
 declare global {
     interface Webui {
         call(fn: string, ...args: DataTypes[]): Promise<string>;
+        on(event: string, callback: (payload: any) => void): () => void;
     }
     var webui: Webui;
 }
\ No newline at end of file
@@ -0,0 +1,25 @@
+// Generated by rust-webui, do not edit.
+
+import type * as T from "./webui-types";
+
+declare const webui: { call(fn: string, ...args: string[]): Promise<string> };
+
+export class WebuiError extends Error {
+    constructor(public readonly detail: T.CommandError) {
+        super(detail.message);
+    }
+}
+
+async function invoke<R>(name: string, ...args: unknown[]): Promise<R> {
+    while (typeof webui === "undefined") {
+        await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+    const response = await webui.call(name, ...args.map((arg) => JSON.stringify(arg)));
+    const result: T.Envelope<R> = JSON.parse(response);
+    if (result.t === "Ok") {
+        return result.c;
+    }
+    throw new WebuiError(result.c);
+}
+
+export const add2 = (arg0: T.Add2Input): Promise<number> => invoke("add2", arg0);
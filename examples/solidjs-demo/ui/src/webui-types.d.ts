@@ -0,0 +1,15 @@
+// Generated by rust-webui, do not edit.
+
+export type CommandError = {
+    kind: "invalid_argument" | "handler" | "internal" | "panic";
+    message: string;
+    index?: number;
+    data?: unknown;
+};
+
+export type Envelope<T> = { t: "Ok"; c: T } | { t: "Err"; c: CommandError };
+
+export interface Add2Input {
+    "x": number;
+    "y": number;
+}
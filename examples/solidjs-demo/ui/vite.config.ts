@@ -2,6 +2,9 @@ import { defineConfig } from 'vite';
 import solidPlugin from 'vite-plugin-solid';
 // import devtools from 'solid-devtools/vite';
 
+// Started by `DevServer` with the port WebUI listens on.
+const webui = `http://127.0.0.1:${process.env.WEBUI_PORT ?? 8080}`;
+
 export default defineConfig({
   plugins: [
     /* 
@@ -14,7 +17,8 @@ export default defineConfig({
   server: {
     port: 3000,
     proxy: {
-      "/webui.js": "http://127.0.0.1:8080"
+      "/webui.js": webui,
+      "/_webui_ws_connect": { target: webui, ws: true },
     }
   },
   build: {
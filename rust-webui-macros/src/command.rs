@@ -0,0 +1,72 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{FnArg, ItemFn, LitStr, ReturnType};
+
+pub fn expand(attr: TokenStream, item: ItemFn) -> syn::Result<TokenStream> {
+    let mut name = item.sig.ident.to_string();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("rename") {
+            name = meta.value()?.parse::<LitStr>()?.value();
+            Ok(())
+        } else {
+            Err(meta.error("expected `rename = \"...\"`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    let sig = &item.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "commands can't be generic",
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "commands can't be async, use `bind_async`",
+        ));
+    }
+
+    let mut args = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Typed(arg) => args.push(&arg.ty),
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "commands can't take `self`",
+                ))
+            }
+        }
+    }
+    let output = match &sig.output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "commands must return a `Result`",
+            ))
+        }
+    };
+
+    let vis = &item.vis;
+    let ident = &sig.ident;
+    Ok(quote! {
+        #item
+
+        #[allow(non_camel_case_types)]
+        #[doc(hidden)]
+        #vis struct #ident {}
+
+        impl ::rust_webui::json::Command for #ident {
+            const NAME: &'static str = #name;
+            type Args = (#(#args,)*);
+            type Handler = fn(#(#args),*) -> #output;
+
+            fn handler() -> Self::Handler {
+                #ident
+            }
+        }
+    })
+}
@@ -0,0 +1,149 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use proc_macro::TokenStream;
+use proc_macro2::Literal;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, ItemFn, LitStr};
+
+mod command;
+mod ts;
+
+/// Embed a directory, e.g. a frontend `dist/` folder, as a
+/// `rust_webui::assets::Bundle`. The path is relative to `CARGO_MANIFEST_DIR`.
+///
+/// ```ignore
+/// static UI: Bundle = rust_webui::include_assets!("ui/dist");
+/// ```
+///
+/// Files are tracked for changes, but adding a new file needs a rebuild of
+/// the crate that invokes the macro.
+#[proc_macro]
+pub fn include_assets(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+    match expand_assets(&lit) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => syn::Error::new(lit.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Implement `rust_webui::ts::TsType`, following serde's `rename` and
+/// `rename_all` attributes.
+///
+/// Structs with named fields become interfaces, newtype structs become
+/// aliases of their field, and enums with only unit variants become string
+/// unions.
+#[proc_macro_derive(TsType, attributes(serde))]
+pub fn derive_ts_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    ts::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turn a function into a `rust_webui::json::Command`, bound by its name or
+/// by `rename`:
+///
+/// ```ignore
+/// #[rust_webui::command(rename = "add")]
+/// fn add_numbers(x: f64, y: f64) -> Result<f64, CommandError> {
+///     Ok(x + y)
+/// }
+///
+/// window.bind_command::<add_numbers>()?;
+/// ```
+///
+/// The function stays callable, the command is a type of the same name.
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    command::expand(attr.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_assets(lit: &LitStr) -> Result<proc_macro2::TokenStream, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "CARGO_MANIFEST_DIR is not set".to_string())?;
+    let root = Path::new(&manifest_dir).join(lit.value());
+    if !root.is_dir() {
+        return Err(format!(
+            "{} is not a directory, build the frontend first",
+            root.display()
+        ));
+    }
+
+    let mut files = Vec::new();
+    collect_files(&root, &mut files).map_err(|err| err.to_string())?;
+    files.sort();
+
+    let mut assets = Vec::new();
+    for file in &files {
+        let relative = file.strip_prefix(&root).unwrap();
+        let mut path = String::new();
+        for component in relative.components() {
+            let component = component
+                .as_os_str()
+                .to_str()
+                .ok_or_else(|| format!("{} is not valid utf8", file.display()))?;
+            path.push('/');
+            path.push_str(component);
+        }
+
+        let data = fs::read(file).map_err(|err| format!("{}: {}", file.display(), err))?;
+        let etag = format!("\"{:016x}\"", fnv1a(&data));
+        let compressed = gzip(&data).map_err(|err| err.to_string())?;
+        let gzip = if compressed.len() < data.len() * 9 / 10 {
+            let compressed = Literal::byte_string(&compressed);
+            quote!(::core::option::Option::Some(#compressed))
+        } else {
+            quote!(::core::option::Option::None)
+        };
+
+        let file = file.to_str().ok_or("path is not valid utf8")?;
+        assets.push(quote! {
+            ::rust_webui::assets::Asset {
+                path: #path,
+                etag: #etag,
+                data: include_bytes!(#file),
+                gzip: #gzip,
+            }
+        });
+    }
+
+    Ok(quote! {
+        ::rust_webui::assets::Bundle {
+            assets: &[#(#assets),*],
+        }
+    })
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn gzip(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
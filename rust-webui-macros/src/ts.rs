@@ -0,0 +1,336 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, LitStr};
+
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    skip: bool,
+}
+
+fn serde_attrs(attrs: &[Attribute]) -> syn::Result<SerdeAttrs> {
+    let mut out = SerdeAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            let nested = meta.input.peek(syn::token::Paren);
+            if (meta.path.is_ident("rename") || meta.path.is_ident("rename_all")) && nested {
+                // Separate serialize and deserialize names.
+                return Err(meta.error("not supported by TsType"));
+            } else if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") {
+                out.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                out.skip = true;
+            } else if ["flatten", "tag", "content", "untagged", "transparent"]
+                .iter()
+                .any(|name| meta.path.is_ident(name))
+            {
+                return Err(meta.error("not supported by TsType"));
+            } else if meta.input.peek(syn::Token![=]) {
+                // Other key-value attributes don't change the shape.
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if nested {
+                meta.parse_nested_meta(|nested| {
+                    if nested.input.peek(syn::Token![=]) {
+                        nested.value()?.parse::<syn::Expr>()?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+/// Serde's `RenameRule::apply_to_field`, fields are written in snake_case.
+fn rename_field(field: &str, rule: &str) -> Option<String> {
+    Some(match rule {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => {
+            let mut pascal = String::new();
+            let mut capitalize = true;
+            for c in field.chars() {
+                if c == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    pascal.push(c.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    pascal.push(c);
+                }
+            }
+            pascal
+        }
+        "camelCase" => lower_first(&rename_field(field, "PascalCase")?),
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        _ => return None,
+    })
+}
+
+/// Serde's `RenameRule::apply_to_variant`, variants are written in
+/// PascalCase.
+fn rename_variant(variant: &str, rule: &str) -> Option<String> {
+    Some(match rule {
+        "PascalCase" => variant.to_string(),
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => lower_first(variant),
+        "snake_case" => {
+            let mut snake = String::new();
+            for (i, c) in variant.char_indices() {
+                if i > 0 && c.is_uppercase() {
+                    snake.push('_');
+                }
+                snake.push(c.to_ascii_lowercase());
+            }
+            snake
+        }
+        "SCREAMING_SNAKE_CASE" => rename_variant(variant, "snake_case")?.to_ascii_uppercase(),
+        "kebab-case" => rename_variant(variant, "snake_case")?.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => {
+            rename_variant(variant, "SCREAMING_SNAKE_CASE")?.replace('_', "-")
+        }
+        _ => return None,
+    })
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+fn serialized_name(
+    ident: &syn::Ident,
+    attrs: &SerdeAttrs,
+    rename_all: Option<&String>,
+    rename: fn(&str, &str) -> Option<String>,
+) -> syn::Result<String> {
+    if let Some(name) = &attrs.rename {
+        return Ok(name.clone());
+    }
+    let name = ident.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+    match rename_all {
+        Some(rule) => rename(name, rule).ok_or_else(|| {
+            syn::Error::new(ident.span(), format!("unknown rename rule {:?}", rule))
+        }),
+        None => Ok(name.to_string()),
+    }
+}
+
+/// `name` as a JSON string, which is a valid TypeScript string literal.
+fn string_literal(name: &str) -> String {
+    serde_json::to_string(name).unwrap()
+}
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "TsType can't be derived for generic types",
+        ));
+    }
+
+    let ident = &input.ident;
+    let container = serde_attrs(&input.attrs)?;
+    let ts_name = container
+        .rename
+        .clone()
+        .unwrap_or_else(|| ident.to_string());
+
+    let declaration = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut declares = Vec::new();
+                let mut lines = Vec::new();
+                for field in &fields.named {
+                    let attrs = serde_attrs(&field.attrs)?;
+                    if attrs.skip {
+                        continue;
+                    }
+                    let name = serialized_name(
+                        field.ident.as_ref().unwrap(),
+                        &attrs,
+                        container.rename_all.as_ref(),
+                        rename_field,
+                    )?;
+                    let ty = &field.ty;
+                    declares.push(quote! {
+                        <#ty as ::rust_webui::ts::TsType>::ts_declare(declarations);
+                    });
+                    let line = format!("    {}: ", string_literal(&name));
+                    lines.push(quote! {
+                        out.push_str(#line);
+                        out.push_str(&<#ty as ::rust_webui::ts::TsType>::ts_type());
+                        out.push_str(";\n");
+                    });
+                }
+                let head = format!("export interface {} {{\n", ts_name);
+                quote! {
+                    #(#declares)*
+                    let mut out = String::from(#head);
+                    #(#lines)*
+                    out.push('}');
+                    out
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                let head = format!("export type {} = ", ts_name);
+                quote! {
+                    <#ty as ::rust_webui::ts::TsType>::ts_declare(declarations);
+                    format!("{}{};", #head, <#ty as ::rust_webui::ts::TsType>::ts_type())
+                }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "TsType needs named fields or a single unnamed field",
+                ))
+            }
+        },
+        Data::Enum(data) => {
+            let mut names = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "TsType only supports enums with unit variants",
+                    ));
+                }
+                let attrs = serde_attrs(&variant.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                names.push(string_literal(&serialized_name(
+                    &variant.ident,
+                    &attrs,
+                    container.rename_all.as_ref(),
+                    rename_variant,
+                )?));
+            }
+            // An enum without variants can't be deserialized.
+            let union = if names.is_empty() {
+                "never".to_string()
+            } else {
+                names.join(" | ")
+            };
+            let declaration = format!("export type {} = {};", ts_name, union);
+            quote! { #declaration.to_string() }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "TsType can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl ::rust_webui::ts::TsType for #ident {
+            fn ts_type() -> String {
+                #ts_name.to_string()
+            }
+
+            fn ts_declare(declarations: &mut ::rust_webui::ts::Declarations) {
+                if declarations.reserve(#ts_name) {
+                    let declaration = { #declaration };
+                    declarations.insert(#ts_name, declaration);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checked against the output of serde_json.
+    #[test]
+    fn rename_rules() {
+        let cases = [
+            ("lowercase", "user_id", "user_id", "HttpError", "httperror"),
+            ("UPPERCASE", "user_id", "USER_ID", "HttpError", "HTTPERROR"),
+            ("PascalCase", "user_id", "UserId", "HttpError", "HttpError"),
+            ("camelCase", "user_id", "userId", "HttpError", "httpError"),
+            (
+                "snake_case",
+                "user_id",
+                "user_id",
+                "HttpError",
+                "http_error",
+            ),
+            (
+                "SCREAMING_SNAKE_CASE",
+                "user_id",
+                "USER_ID",
+                "HttpError",
+                "HTTP_ERROR",
+            ),
+            (
+                "kebab-case",
+                "user_id",
+                "user-id",
+                "HttpError",
+                "http-error",
+            ),
+            (
+                "SCREAMING-KEBAB-CASE",
+                "user_id",
+                "USER-ID",
+                "HttpError",
+                "HTTP-ERROR",
+            ),
+        ];
+        for (rule, field, renamed_field, variant, renamed_variant) in cases {
+            assert_eq!(
+                rename_field(field, rule).as_deref(),
+                Some(renamed_field),
+                "{}",
+                rule
+            );
+            assert_eq!(
+                rename_variant(variant, rule).as_deref(),
+                Some(renamed_variant),
+                "{}",
+                rule
+            );
+        }
+        assert_eq!(rename_field("user_id", "Title Case"), None);
+        assert_eq!(rename_variant("HttpError", "Title Case"), None);
+    }
+
+    #[test]
+    fn unsupported_attributes() {
+        let unsupported: [Attribute; 7] = [
+            syn::parse_quote!(#[serde(untagged)]),
+            syn::parse_quote!(#[serde(tag = "type")]),
+            syn::parse_quote!(#[serde(tag = "t", content = "c")]),
+            syn::parse_quote!(#[serde(transparent)]),
+            syn::parse_quote!(#[serde(flatten)]),
+            syn::parse_quote!(#[serde(rename(serialize = "a"))]),
+            syn::parse_quote!(#[serde(rename_all(serialize = "camelCase"))]),
+        ];
+        for attr in unsupported {
+            assert!(serde_attrs(&[attr]).is_err());
+        }
+        let attr: Attribute = syn::parse_quote!(#[serde(default, rename = "a")]);
+        assert_eq!(serde_attrs(&[attr]).unwrap().rename.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn string_literals_are_escaped() {
+        assert_eq!(string_literal("a\"b"), r#""a\"b""#);
+        assert_eq!(string_literal("é"), "\"é\"");
+        assert_eq!(string_literal("\u{1}"), r#""\u0001""#);
+    }
+}
@@ -1,8 +1,8 @@
 use rust_webui::Window;
 
-fn main() {
+fn main() -> rust_webui::Result<()> {
     let window = Window::new();
-    let res = window.show(
+    window.show(
         r#"
     <html>
     <script src="webui.js"></script>
@@ -11,19 +11,20 @@ fn main() {
     <button onclick="webui.call('func1', 'hello', 1, true).then(res=>console.log(res, typeof(res)))">pass args</button>
     </html>
     "#
-    );
-    dbg!(res);
+    )?;
     // window.bind("x", |event| {
     //     dbg!(&event);
     //     event.set_response("hello");
     // });
     window.bind("func1", |event| {
+        dbg!(event.arg_count());
         dbg!(event.get_string_at(0));
         dbg!(event.get_int_at(1));
         dbg!(event.get_bool_at(2));
 
-        event.set_response("1");
-    });
+        1
+    })?;
     rust_webui::wait();
     // rust_webui::clean();
+    Ok(())
 }
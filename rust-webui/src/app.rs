@@ -0,0 +1,248 @@
+use std::{
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{webui::live_windows, Browser, Error, Profile, Result, Runtime, Window, WindowRef};
+
+/// Application wide settings, applied in the order WebUI needs them.
+///
+/// ```no_run
+/// # use rust_webui::{App, Browser};
+/// let app = App::builder()
+///     .timeout(30)
+///     .browser(Browser::Firefox)
+///     .ports(8080..=8090)
+///     .build()?;
+/// let window = app.window()?;
+/// app.show(&window, "index.html")?;
+/// app.run();
+/// # Ok::<(), rust_webui::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct App {
+    browser: Option<Browser>,
+    runtime: Option<Runtime>,
+    ports: Option<RangeInclusive<usize>>,
+    next_port: AtomicUsize,
+    profile: Option<Profile>,
+}
+
+#[derive(Debug, Default)]
+pub struct AppBuilder {
+    timeout: Option<usize>,
+    root_folder: Option<PathBuf>,
+    browser: Option<Browser>,
+    runtime: Option<Runtime>,
+    ports: Option<RangeInclusive<usize>>,
+    profile: Option<Profile>,
+    #[cfg(feature = "tls")]
+    tls: Option<(String, String)>,
+}
+
+impl App {
+    pub fn builder() -> AppBuilder {
+        AppBuilder::default()
+    }
+
+    /// Create a window with the runtime, profile and the next free port of
+    /// the app. Ports are handed out in order and wrap around, so ports of
+    /// destroyed windows are used again.
+    pub fn window(&self) -> Result<Window> {
+        let mut window = Window::new();
+        if let Some(runtime) = self.runtime {
+            window.set_runtime(runtime);
+        }
+        if let Some(profile) = &self.profile {
+            window.use_profile(profile.clone())?;
+        }
+        if let Some(ports) = &self.ports {
+            let next = self.next_port.load(Ordering::Relaxed);
+            let start = if ports.contains(&next) {
+                next
+            } else {
+                *ports.start()
+            };
+            let port = (start..=*ports.end())
+                .chain(*ports.start()..start)
+                .find(|&port| window.set_port(port).is_ok())
+                .ok_or_else(|| Error::PortsExhausted(ports.clone()))?;
+            self.next_port.store(port + 1, Ordering::Relaxed);
+        }
+        Ok(window)
+    }
+
+    /// Show a window in the preferred browser, or in any browser if there is
+    /// no preference.
+    pub fn show(&self, window: &WindowRef, content: &str) -> Result<()> {
+        match self.browser {
+            Some(browser) => window.show_browser(content, browser),
+            None => window.show(content),
+        }
+    }
+
+    /// Wait until all windows are closed, then free all resources.
+    pub fn run(self) {
+        crate::wait();
+        crate::clean();
+    }
+}
+
+impl AppBuilder {
+    /// Seconds to wait for the first window to connect, `0` waits forever.
+    pub fn timeout(mut self, seconds: usize) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Root folder of windows without their own, see
+    /// [`set_default_root_folder`](crate::set_default_root_folder).
+    pub fn root_folder(mut self, path: impl Into<PathBuf>) -> Self {
+        self.root_folder = Some(path.into());
+        self
+    }
+
+    pub fn browser(mut self, browser: Browser) -> Self {
+        self.browser = Some(browser);
+        self
+    }
+
+    pub fn runtime(mut self, runtime: Runtime) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Give every window the next free port in `ports`, instead of a random
+    /// one.
+    pub fn ports(mut self, ports: RangeInclusive<usize>) -> Self {
+        self.ports = Some(ports);
+        self
+    }
+
+    /// Browser profile of every window, an ephemeral profile gives each
+    /// window its own.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// See [`set_tls_certificate`](crate::set_tls_certificate).
+    #[cfg(feature = "tls")]
+    pub fn tls_certificate(
+        mut self,
+        certificate_pem: impl Into<String>,
+        private_key_pem: impl Into<String>,
+    ) -> Self {
+        self.tls = Some((certificate_pem.into(), private_key_pem.into()));
+        self
+    }
+
+    /// See [`set_self_signed_tls`](crate::set_self_signed_tls).
+    #[cfg(feature = "tls")]
+    pub fn self_signed_tls(self) -> Self {
+        self.tls_certificate("", "")
+    }
+
+    /// Check the settings and apply the global ones. Fails if a window is
+    /// already shown, since WebUI reads them when the first window starts.
+    pub fn build(self) -> Result<App> {
+        if live_windows().iter().any(WindowRef::is_shown) {
+            return Err(Error::InvalidConfig(
+                "the app must be built before a window is shown",
+            ));
+        }
+        if self.ports.as_ref().is_some_and(|ports| ports.is_empty()) {
+            return Err(Error::InvalidConfig("the port range is empty"));
+        }
+        if let Some(path) = &self.root_folder {
+            if !path.is_dir() {
+                return Err(Error::InvalidRootFolder(path.clone()));
+            }
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some((certificate_pem, private_key_pem)) = &self.tls {
+            crate::set_tls_certificate(certificate_pem, private_key_pem)?;
+        }
+        if let Some(path) = &self.root_folder {
+            crate::set_default_root_folder(path)?;
+        }
+        if let Some(seconds) = self.timeout {
+            crate::set_timeout(seconds);
+        }
+
+        Ok(App {
+            browser: self.browser,
+            runtime: self.runtime,
+            next_port: AtomicUsize::new(0),
+            ports: self.ports,
+            profile: self.profile,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBackend;
+
+    #[test]
+    fn ports_wrap_around() {
+        let mock = MockBackend::install();
+        let app = App::builder().ports(48300..=48302).build().unwrap();
+        let first = app.window().unwrap();
+        let second = app.window().unwrap();
+        let third = app.window().unwrap();
+        let ports: Vec<_> = [&first, &second, &third]
+            .map(|window| mock.settings(window).port)
+            .into();
+        assert_eq!(ports, [Some(48300), Some(48301), Some(48302)]);
+        assert!(matches!(
+            app.window(),
+            Err(Error::PortsExhausted(ports)) if ports == (48300..=48302)
+        ));
+
+        drop(second);
+        let again = app.window().unwrap();
+        assert_eq!(mock.settings(&again).port, Some(48301));
+    }
+
+    #[test]
+    fn window_settings() {
+        let mock = MockBackend::install();
+        let app = App::builder()
+            .timeout(5)
+            .runtime(Runtime::NodeJs)
+            .profile(Profile::named("app", "/tmp/app-profile"))
+            .build()
+            .unwrap();
+        assert_eq!(mock.globals().timeout, Some(5));
+
+        let window = app.window().unwrap();
+        let settings = mock.settings(&window);
+        assert_eq!(settings.runtime, Some(Runtime::NodeJs));
+        assert_eq!(
+            settings.profile,
+            Some(("app".to_string(), "/tmp/app-profile".to_string()))
+        );
+        assert_eq!(settings.port, None);
+    }
+
+    #[test]
+    fn invalid_config() {
+        let _mock = MockBackend::install();
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = App::builder().ports(2..=1).build();
+        assert!(matches!(empty, Err(Error::InvalidConfig(_))));
+        let missing = App::builder().root_folder("/no/such/folder").build();
+        assert!(matches!(missing, Err(Error::InvalidRootFolder(_))));
+
+        let window = Window::new();
+        window.show("<html></html>").unwrap();
+        assert!(matches!(
+            App::builder().build(),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+}
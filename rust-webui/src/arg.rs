@@ -0,0 +1,94 @@
+use crate::{ArgError, Event};
+
+/// A type that can be read from a `webui.call` argument, see [`Event::arg`].
+pub trait FromArg<'a>: Sized {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError>;
+}
+
+impl<'e> Event<'e> {
+    /// Read argument `index`, e.g. `event.arg::<u32>(0)?`.
+    ///
+    /// Numbers and booleans must be present, strings and bytes are empty when
+    /// missing, and `Option<T>` is `None` for missing or empty arguments.
+    pub fn arg<'a, T: FromArg<'a>>(&'a self, index: usize) -> Result<T, ArgError> {
+        T::from_arg(self, index)
+    }
+}
+
+fn text<'a>(event: &'a Event, index: usize) -> Result<&'a str, ArgError> {
+    event
+        .get_str_at(index)
+        .map_err(|err| ArgError::InvalidUtf8(index, err))
+}
+
+fn parse<T: std::str::FromStr>(
+    event: &Event,
+    index: usize,
+    expected: &'static str,
+) -> Result<T, ArgError> {
+    let text = text(event, index)?;
+    if text.is_empty() {
+        return Err(ArgError::Missing(index));
+    }
+    text.parse()
+        .map_err(|_| ArgError::Invalid { index, expected })
+}
+
+macro_rules! impl_from_arg_number {
+    ($($ty:ty),*) => {
+        $(impl<'a> FromArg<'a> for $ty {
+            fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+                parse(event, index, stringify!($ty))
+            }
+        })*
+    };
+}
+
+impl_from_arg_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl<'a> FromArg<'a> for bool {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        match text(event, index)? {
+            "" => Err(ArgError::Missing(index)),
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            _ => Err(ArgError::Invalid {
+                index,
+                expected: "bool",
+            }),
+        }
+    }
+}
+
+impl<'a> FromArg<'a> for &'a str {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        text(event, index)
+    }
+}
+
+impl<'a> FromArg<'a> for String {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        text(event, index).map(str::to_string)
+    }
+}
+
+impl<'a> FromArg<'a> for &'a [u8] {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        Ok(event.get_bytes_at(index))
+    }
+}
+
+impl<'a> FromArg<'a> for Vec<u8> {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        Ok(event.get_bytes_at(index).to_vec())
+    }
+}
+
+impl<'a, T: FromArg<'a>> FromArg<'a> for Option<T> {
+    fn from_arg(event: &'a Event, index: usize) -> Result<Self, ArgError> {
+        if event.get_bytes_at(index).is_empty() {
+            return Ok(None);
+        }
+        T::from_arg(event, index).map(Some)
+    }
+}
@@ -0,0 +1,257 @@
+//! Frontend assets embedded in the binary.
+//!
+//! ```ignore
+//! use rust_webui::{assets::Bundle, Window};
+//!
+//! static UI: Bundle = rust_webui::include_assets!("ui/dist");
+//!
+//! let mut window = Window::new();
+//! window.serve_embedded(&UI)?;
+//! window.show("index.html")?;
+//! ```
+//!
+//! Assets keep their uncompressed content, plus a gzip compressed copy when
+//! that is notably smaller, and an ETag. [`Asset::response_to`] answers
+//! `If-None-Match` with `304 Not Modified` and only sends the compressed copy
+//! if `Accept-Encoding` allows it.
+//!
+//! WebUI only passes the request path to file handlers, so
+//! [`serve_embedded`](WindowRef::serve_embedded) uses [`Asset::response`]:
+//! the compressed copy, which every browser WebUI launches accepts, and
+//! always the full content.
+
+use crate::{mime_type, Response, Result, WindowRef};
+
+pub use rust_webui_macros::include_assets;
+
+/// A set of files created by [`include_assets!`], sorted by path.
+#[derive(Debug)]
+pub struct Bundle {
+    pub assets: &'static [Asset],
+}
+
+#[derive(Debug)]
+pub struct Asset {
+    /// Absolute URL path, e.g. `/assets/index.js`.
+    pub path: &'static str,
+    /// Quoted ETag of the uncompressed content.
+    pub etag: &'static str,
+    /// The uncompressed content.
+    pub data: &'static [u8],
+    /// The gzip compressed content, if it is notably smaller.
+    pub gzip: Option<&'static [u8]>,
+}
+
+impl Bundle {
+    pub fn get(&self, path: &str) -> Option<&'static Asset> {
+        self.assets
+            .binary_search_by(|asset| asset.path.cmp(path))
+            .ok()
+            .map(|index| &self.assets[index])
+    }
+
+    /// Resolve a request path. Directories map to their `index.html`, and
+    /// unknown paths without an extension fall back to `/index.html` so
+    /// client-side routing works.
+    pub fn resolve(&self, path: &str) -> Option<&'static Asset> {
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        if path.is_empty() || path.ends_with('/') {
+            return self.get(&format!("{}/index.html", path.trim_end_matches('/')));
+        }
+        if let Some(asset) = self.get(path) {
+            return Some(asset);
+        }
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        if file_name.contains('.') {
+            None
+        } else {
+            self.get("/index.html")
+        }
+    }
+
+    pub fn response(&self, path: &str) -> Option<Response> {
+        self.resolve(path).map(Asset::response)
+    }
+
+    /// Like [`response`](Self::response), for a request with these headers.
+    pub fn response_to(&self, path: &str, headers: &[(&str, &str)]) -> Option<Response> {
+        self.resolve(path).map(|asset| asset.response_to(headers))
+    }
+}
+
+impl Asset {
+    /// The response when the request headers are unknown, the compressed
+    /// content if there is any, see the [module docs](self).
+    pub fn response(&self) -> Response {
+        self.full_response(self.gzip.is_some())
+    }
+
+    /// The response to a request with these headers: `304 Not Modified` if
+    /// `If-None-Match` has the ETag, otherwise the compressed content if
+    /// `Accept-Encoding` allows gzip and the uncompressed content if not.
+    pub fn response_to(&self, headers: &[(&str, &str)]) -> Response {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|&(_, value)| value)
+        };
+        if header("If-None-Match").is_some_and(|tags| self.matches(tags)) {
+            return self.headers(Response::new(Vec::new()).with_status(304));
+        }
+        let gzip = header("Accept-Encoding").is_some_and(accepts_gzip);
+        self.full_response(gzip && self.gzip.is_some())
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        mime_type(self.path)
+    }
+
+    fn full_response(&self, gzip: bool) -> Response {
+        let response = match self.gzip {
+            Some(data) if gzip => {
+                Response::from_path(self.path, data).with_header("Content-Encoding", "gzip")
+            }
+            _ => Response::from_path(self.path, self.data),
+        };
+        self.headers(response)
+    }
+
+    fn headers(&self, response: Response) -> Response {
+        let response = response
+            .with_header("ETag", self.etag)
+            .with_header("Cache-Control", "no-cache");
+        if self.gzip.is_some() {
+            response.with_header("Vary", "Accept-Encoding")
+        } else {
+            response
+        }
+    }
+
+    /// Whether an `If-None-Match` value has the ETag, weak or not.
+    fn matches(&self, tags: &str) -> bool {
+        tags.split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.etag)
+    }
+}
+
+/// Whether an `Accept-Encoding` value allows gzip, `q=0` refuses it.
+fn accepts_gzip(encodings: &str) -> bool {
+    let mut any = false;
+    for encoding in encodings.split(',') {
+        let mut params = encoding.split(';').map(str::trim);
+        let name = params.next().unwrap_or_default();
+        let accepted = params
+            .filter_map(|param| param.strip_prefix("q="))
+            .all(|q| q.parse::<f32>().map_or(true, |q| q > 0.0));
+        if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+            return accepted;
+        }
+        if name == "*" {
+            any = accepted;
+        }
+    }
+    any
+}
+
+impl WindowRef {
+    /// Serve an embedded bundle, falling back to the root folder for paths
+    /// that are not in it.
+    pub fn serve_embedded(&mut self, bundle: &'static Bundle) -> Result<()> {
+        self.set_file_handler(move |path| bundle.response(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static BUNDLE: Bundle = Bundle {
+        assets: &[
+            Asset {
+                path: "/assets/index.js",
+                etag: "\"1\"",
+                data: b"console.log(1)",
+                gzip: Some(b"gzip"),
+            },
+            Asset {
+                path: "/index.html",
+                etag: "\"2\"",
+                data: b"",
+                gzip: None,
+            },
+        ],
+    };
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response
+            .headers()
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let resolve = |path| BUNDLE.resolve(path).map(|asset| asset.path);
+        assert_eq!(resolve("/"), Some("/index.html"));
+        assert_eq!(resolve("/assets/index.js?v=2"), Some("/assets/index.js"));
+        assert_eq!(resolve("/settings/profile"), Some("/index.html"));
+        assert_eq!(resolve("/missing.png"), None);
+    }
+
+    #[test]
+    fn content_encoding() {
+        let script = BUNDLE.get("/assets/index.js").unwrap();
+        let response = script.response();
+        assert_eq!(response.body(), b"gzip");
+        assert_eq!(header(&response, "Content-Encoding"), Some("gzip"));
+        assert_eq!(header(&response, "ETag"), Some("\"1\""));
+        assert_eq!(header(&response, "Vary"), Some("Accept-Encoding"));
+
+        for (encodings, gzip) in [
+            ("gzip, deflate, br", true),
+            ("br;q=1.0, GZIP;q=0.5", true),
+            ("*", true),
+            ("deflate, br", false),
+            ("gzip;q=0, *", false),
+            ("identity", false),
+        ] {
+            let response = script.response_to(&[("accept-encoding", encodings)]);
+            assert_eq!(
+                header(&response, "Content-Encoding").is_some(),
+                gzip,
+                "{}",
+                encodings
+            );
+            let body: &[u8] = if gzip { b"gzip" } else { b"console.log(1)" };
+            assert_eq!(response.body(), body, "{}", encodings);
+        }
+        let response = script.response_to(&[]);
+        assert_eq!(response.body(), b"console.log(1)");
+        assert_eq!(
+            header(&response, "Content-Type"),
+            Some("text/javascript; charset=utf-8")
+        );
+
+        let page = BUNDLE
+            .response_to("/", &[("Accept-Encoding", "gzip")])
+            .unwrap();
+        assert_eq!(header(&page, "Content-Encoding"), None);
+        assert_eq!(header(&page, "Vary"), None);
+    }
+
+    #[test]
+    fn if_none_match() {
+        let script = BUNDLE.get("/assets/index.js").unwrap();
+        for tags in ["\"1\"", "W/\"1\"", "\"0\", \"1\"", "*"] {
+            let response = script.response_to(&[("If-None-Match", tags)]);
+            assert_eq!(response.status(), 304, "{}", tags);
+            assert!(response.body().is_empty());
+            assert_eq!(header(&response, "ETag"), Some("\"1\""));
+        }
+        let response = script.response_to(&[("If-None-Match", "\"2\"")]);
+        assert_eq!(response.status(), 200);
+    }
+}
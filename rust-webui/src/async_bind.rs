@@ -0,0 +1,211 @@
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::atomic::{AtomicI64, Ordering},
+    task::{Context, Poll},
+};
+
+use crate::{
+    hooks::install_client,
+    webui::{json_string, panic_message},
+    Binding, Event, Result, WindowRef,
+};
+
+static NEXT_CALL_ID: AtomicI64 = AtomicI64::new(0);
+
+/// Where futures run. With both the `tokio` and `async-std` features, the
+/// current tokio runtime is used if there is one, async-std otherwise.
+#[derive(Clone)]
+enum Spawner {
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::runtime::Handle),
+    #[cfg(feature = "async-std")]
+    AsyncStd,
+}
+
+impl Spawner {
+    fn current() -> Result<Self> {
+        #[cfg(feature = "tokio")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            return Ok(Spawner::Tokio(handle));
+        }
+        #[cfg(feature = "async-std")]
+        return Ok(Spawner::AsyncStd);
+        #[cfg(not(feature = "async-std"))]
+        Err(crate::Error::NoRuntime)
+    }
+
+    fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        match self {
+            #[cfg(feature = "tokio")]
+            Spawner::Tokio(handle) => drop(handle.spawn(future)),
+            #[cfg(feature = "async-std")]
+            Spawner::AsyncStd => drop(async_std::task::spawn(future)),
+        }
+    }
+}
+
+/// Polls the inner future, turning a panic into `Err(message)`.
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = std::result::Result<F::Output, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.0.as_mut();
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(panic_message(payload.as_ref()).to_string())),
+        }
+    }
+}
+
+impl WindowRef {
+    /// Bind a closure that returns a future. The closure runs on the WebUI
+    /// thread to read the arguments, the future runs on the async runtime.
+    ///
+    /// The page has to call it through the rust-webui client, see
+    /// [`CLIENT_JS`](crate::CLIENT_JS):
+    ///
+    /// ```js
+    /// const user = await rustWebui.callAsync("load_user", id);
+    /// ```
+    ///
+    /// The client is run in the window when the page connects. Pages that
+    /// call `rustWebui` before that have to load `CLIENT_JS` themselves.
+    ///
+    /// With only the `tokio` feature this must be called from within a
+    /// runtime.
+    pub fn bind_async<F, Fut>(&self, element: &str, func: F) -> Result<Binding>
+    where
+        F: Fn(&mut Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = String> + Send + 'static,
+    {
+        let spawner = Spawner::current()?;
+        install_client(self)?;
+        self.bind(element, move |event| {
+            let id = NEXT_CALL_ID.fetch_add(1, Ordering::Relaxed);
+            let window = event.window;
+            let future = CatchUnwind(Box::pin(func(event)));
+            event.return_int(id);
+
+            spawner.spawn(async move {
+                let script = match future.await {
+                    Ok(output) => format!("rustWebui._resolve({}, {});", id, json_string(&output)),
+                    Err(message) => {
+                        log::error!("async binding panicked: {}", message);
+                        format!("rustWebui._reject({}, {});", id, json_string(&message))
+                    }
+                };
+                if let Err(err) = window.run(&script) {
+                    log::warn!("failed to deliver async response {}: {}", id, err);
+                }
+            });
+        })
+    }
+}
+
+/// Async version of [`wait`](crate::wait), the blocking wait runs on the
+/// runtime's blocking pool, see [`WindowRef::bind_async`] for which runtime
+/// is used.
+///
+/// If the future is dropped before all windows are closed, e.g. because the
+/// runtime shuts down, [`exit`](crate::exit) is called so the blocking thread
+/// can finish.
+pub async fn wait_async() {
+    struct ExitOnDrop(bool);
+
+    impl Drop for ExitOnDrop {
+        fn drop(&mut self) {
+            if !self.0 {
+                crate::exit();
+            }
+        }
+    }
+
+    let mut guard = ExitOnDrop(false);
+    match Spawner::current() {
+        #[cfg(feature = "tokio")]
+        Ok(Spawner::Tokio(handle)) => {
+            if let Err(err) = handle.spawn_blocking(crate::wait).await {
+                log::error!("wait failed: {}", err);
+            }
+        }
+        #[cfg(feature = "async-std")]
+        Ok(Spawner::AsyncStd) => async_std::task::spawn_blocking(crate::wait).await,
+        Err(err) => {
+            log::warn!("{}, waiting on the current thread", err);
+            crate::wait();
+        }
+    }
+    guard.0 = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+
+    use crate::{mock::MockBackend, Window};
+
+    #[cfg(feature = "tokio")]
+    fn block_on(future: impl Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn block_on(future: impl Future<Output = ()>) {
+        async_std::task::block_on(future)
+    }
+
+    async fn yield_now() {
+        #[cfg(feature = "tokio")]
+        tokio::task::yield_now().await;
+        #[cfg(not(feature = "tokio"))]
+        async_std::task::yield_now().await;
+    }
+
+    #[test]
+    fn settle_promise_when_the_future_completes() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window.show("<html></html>").unwrap();
+
+        block_on(async {
+            window
+                .bind_async("double", |event| {
+                    let n = event.get_int_at(0);
+                    async move {
+                        yield_now().await;
+                        (n * 2).to_string()
+                    }
+                })
+                .unwrap();
+            window
+                .bind_async("boom", |_| async { panic!("boom") })
+                .unwrap();
+
+            let id = mock.call(&window, "double", &["21"]).unwrap();
+            let boom = mock.call(&window, "boom", &[]).unwrap();
+            let resolved = format!("rustWebui._resolve({}, \"42\");", id);
+            let rejected = format!("rustWebui._reject({}, \"boom\");", boom);
+            for _ in 0..1000 {
+                let runs = mock.runs(&window);
+                if runs.contains(&resolved) && runs.contains(&rejected) {
+                    break;
+                }
+                // async-std runs the futures on other threads.
+                yield_now().await;
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            let runs = mock.runs(&window);
+            assert_eq!(runs[0], crate::CLIENT_JS);
+            assert!(runs.contains(&resolved), "{:?}", &runs[1..]);
+            assert!(runs.contains(&rejected), "{:?}", &runs[1..]);
+        });
+    }
+}
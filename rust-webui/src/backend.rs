@@ -0,0 +1,309 @@
+//! The calls into WebUI, so windows and bindings can run against something
+//! else than the C library, e.g. the `MockBackend` of the `mock` feature in
+//! tests.
+
+use once_cell::sync::Lazy;
+use std::{
+    ffi::CStr,
+    sync::{Arc, RwLock},
+};
+
+use webui_sys as ffi;
+
+use crate::{file_handler::raw_file_handler, webui::event_handler, Browser, Runtime};
+
+/// Every call rust-webui makes into WebUI: global settings, window creation,
+/// settings, showing, bindings, scripts and responses. Only file handler
+/// responses are allocated with `webui_malloc` directly, WebUI frees them.
+///
+/// Events for bound elements are delivered by calling the handler of the
+/// binding, see `MockBackend::fire`.
+pub trait Backend: Send + Sync {
+    /// Seconds to wait for the first window to connect, `0` waits forever.
+    fn set_timeout(&self, seconds: usize);
+    /// Returns `false` if `path` is not a folder.
+    fn set_default_root_folder(&self, path: &CStr) -> bool;
+    /// Empty PEM strings make WebUI generate a self-signed certificate.
+    /// Returns `false` if the certificate is invalid.
+    #[cfg(feature = "tls")]
+    fn set_tls_certificate(&self, certificate_pem: &CStr, private_key_pem: &CStr) -> bool;
+    fn is_app_running(&self) -> bool;
+    /// The next free window number, without reserving it.
+    fn get_new_window_id(&self) -> usize;
+    fn new_window(&self) -> usize;
+    fn new_window_id(&self, window: usize);
+    fn destroy(&self, window: usize);
+    fn get_unique_window_id(&self, window: usize) -> usize;
+    fn set_size(&self, window: usize, width: u32, height: u32);
+    fn set_position(&self, window: usize, x: u32, y: u32);
+    fn set_icon(&self, window: usize, icon: &CStr, icon_type: &CStr);
+    fn set_runtime(&self, window: usize, runtime: Runtime);
+    fn set_public(&self, window: usize, public: bool);
+    fn set_hide(&self, window: usize, hide: bool);
+    fn set_kiosk(&self, window: usize, kiosk: bool);
+    /// Returns `false` if `path` is not a folder.
+    fn set_root_folder(&self, window: usize, path: &CStr) -> bool;
+    /// Returns `false` if `port` is in use.
+    fn set_port(&self, window: usize, port: usize) -> bool;
+    /// Serve the window's files with the handler set by
+    /// [`set_file_handler`](crate::WindowRef::set_file_handler).
+    fn set_file_handler(&self, window: usize);
+    /// An empty `name` and `path` means the default profile.
+    fn set_profile(&self, window: usize, name: &CStr, path: &CStr);
+    fn delete_profile(&self, window: usize);
+    fn delete_all_profiles(&self);
+    fn show(&self, window: usize, content: &CStr) -> bool;
+    fn show_browser(&self, window: usize, content: &CStr, browser: Browser) -> bool;
+    fn is_shown(&self, window: usize) -> bool;
+    fn close(&self, window: usize);
+    fn navigate(&self, window: usize, url: &CStr);
+    /// The full URL of the window, `None` before it is shown.
+    fn get_url(&self, window: usize) -> Option<String>;
+    /// Bind `element` and return its bind id. Binding the same element again
+    /// returns the same id.
+    fn bind(&self, window: usize, element: &CStr) -> usize;
+    fn run(&self, window: usize, script: &CStr);
+    fn send_raw(&self, window: usize, function: &CStr, data: &[u8]);
+    /// Run `script` and write the NUL terminated result into `buffer`, like
+    /// `webui_script`. Returns `false` if the script threw or timed out.
+    fn script(&self, window: usize, script: &CStr, timeout: usize, buffer: &mut [u8]) -> bool;
+    /// Argument `index` of an event, null if there is none. The pointer must
+    /// stay valid until the handler of the event returns.
+    fn arg_at(&self, window: usize, event_number: usize, index: usize) -> (*const u8, usize);
+    fn int_at(&self, window: usize, event_number: usize, index: usize) -> i64;
+    fn bool_at(&self, window: usize, event_number: usize, index: usize) -> bool;
+    fn set_response(&self, window: usize, event_number: usize, response: &CStr);
+    fn return_int(&self, window: usize, event_number: usize, n: i64);
+    fn return_bool(&self, window: usize, event_number: usize, b: bool);
+    fn wait(&self);
+    fn exit(&self);
+    fn clean(&self);
+}
+
+/// The WebUI C library, the default backend.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FfiBackend;
+
+impl FfiBackend {
+    fn event(window: usize, event_number: usize) -> ffi::webui_event_t {
+        ffi::webui_event_t {
+            window,
+            event_type: 0,
+            element: std::ptr::null_mut(),
+            event_number,
+            bind_id: 0,
+        }
+    }
+}
+
+impl Backend for FfiBackend {
+    fn set_timeout(&self, seconds: usize) {
+        unsafe { ffi::webui_set_timeout(seconds) }
+    }
+
+    fn set_default_root_folder(&self, path: &CStr) -> bool {
+        unsafe { ffi::webui_set_default_root_folder(path.as_ptr()) }
+    }
+
+    #[cfg(feature = "tls")]
+    fn set_tls_certificate(&self, certificate_pem: &CStr, private_key_pem: &CStr) -> bool {
+        unsafe {
+            ffi::webui_set_tls_certificate(certificate_pem.as_ptr(), private_key_pem.as_ptr())
+        }
+    }
+
+    fn is_app_running(&self) -> bool {
+        unsafe { ffi::webui_interface_is_app_running() }
+    }
+
+    fn get_new_window_id(&self) -> usize {
+        unsafe { ffi::webui_get_new_window_id() }
+    }
+
+    fn new_window(&self) -> usize {
+        unsafe { ffi::webui_new_window() }
+    }
+
+    fn new_window_id(&self, window: usize) {
+        unsafe { ffi::webui_new_window_id(window) };
+    }
+
+    fn destroy(&self, window: usize) {
+        unsafe { ffi::webui_destroy(window) }
+    }
+
+    fn get_unique_window_id(&self, window: usize) -> usize {
+        unsafe { ffi::webui_interface_get_window_id(window) }
+    }
+
+    fn set_size(&self, window: usize, width: u32, height: u32) {
+        unsafe { ffi::webui_set_size(window, width, height) }
+    }
+
+    fn set_position(&self, window: usize, x: u32, y: u32) {
+        unsafe { ffi::webui_set_position(window, x, y) }
+    }
+
+    fn set_icon(&self, window: usize, icon: &CStr, icon_type: &CStr) {
+        unsafe { ffi::webui_set_icon(window, icon.as_ptr(), icon_type.as_ptr()) }
+    }
+
+    fn set_runtime(&self, window: usize, runtime: Runtime) {
+        unsafe { ffi::webui_set_runtime(window, runtime as usize) }
+    }
+
+    fn set_public(&self, window: usize, public: bool) {
+        unsafe { ffi::webui_set_public(window, public) }
+    }
+
+    fn set_hide(&self, window: usize, hide: bool) {
+        unsafe { ffi::webui_set_hide(window, hide) }
+    }
+
+    fn set_kiosk(&self, window: usize, kiosk: bool) {
+        unsafe { ffi::webui_set_kiosk(window, kiosk) }
+    }
+
+    fn set_root_folder(&self, window: usize, path: &CStr) -> bool {
+        unsafe { ffi::webui_set_root_folder(window, path.as_ptr()) }
+    }
+
+    fn set_port(&self, window: usize, port: usize) -> bool {
+        unsafe { ffi::webui_set_port(window, port) }
+    }
+
+    fn set_file_handler(&self, window: usize) {
+        unsafe { ffi::webui_set_file_handler(window, Some(raw_file_handler(window))) }
+    }
+
+    fn set_profile(&self, window: usize, name: &CStr, path: &CStr) {
+        unsafe { ffi::webui_set_profile(window, name.as_ptr(), path.as_ptr()) }
+    }
+
+    fn delete_profile(&self, window: usize) {
+        unsafe { ffi::webui_delete_profile(window) }
+    }
+
+    fn delete_all_profiles(&self) {
+        unsafe { ffi::webui_delete_all_profiles() }
+    }
+
+    fn show(&self, window: usize, content: &CStr) -> bool {
+        unsafe { ffi::webui_show(window, content.as_ptr()) }
+    }
+
+    fn show_browser(&self, window: usize, content: &CStr, browser: Browser) -> bool {
+        unsafe { ffi::webui_show_browser(window, content.as_ptr(), browser as usize) }
+    }
+
+    fn is_shown(&self, window: usize) -> bool {
+        unsafe { ffi::webui_is_shown(window) }
+    }
+
+    fn close(&self, window: usize) {
+        unsafe { ffi::webui_close(window) }
+    }
+
+    fn navigate(&self, window: usize, url: &CStr) {
+        unsafe { ffi::webui_navigate(window, url.as_ptr()) }
+    }
+
+    fn get_url(&self, window: usize) -> Option<String> {
+        let ptr = unsafe { ffi::webui_get_url(window) };
+        if ptr.is_null() {
+            return None;
+        }
+        let url = unsafe { CStr::from_ptr(ptr) }.to_string_lossy();
+        if url.is_empty() {
+            None
+        } else {
+            Some(url.into_owned())
+        }
+    }
+
+    fn bind(&self, window: usize, element: &CStr) -> usize {
+        unsafe { ffi::webui_interface_bind(window, element.as_ptr(), Some(event_handler)) }
+    }
+
+    fn run(&self, window: usize, script: &CStr) {
+        unsafe { ffi::webui_run(window, script.as_ptr()) }
+    }
+
+    fn send_raw(&self, window: usize, function: &CStr, data: &[u8]) {
+        unsafe {
+            ffi::webui_send_raw(
+                window,
+                function.as_ptr(),
+                data.as_ptr() as *const _,
+                data.len(),
+            )
+        }
+    }
+
+    fn script(&self, window: usize, script: &CStr, timeout: usize, buffer: &mut [u8]) -> bool {
+        unsafe {
+            ffi::webui_script(
+                window,
+                script.as_ptr(),
+                timeout,
+                buffer.as_mut_ptr() as *mut _,
+                buffer.len(),
+            )
+        }
+    }
+
+    fn arg_at(&self, window: usize, event_number: usize, index: usize) -> (*const u8, usize) {
+        unsafe {
+            let ptr = ffi::webui_interface_get_string_at(window, event_number, index);
+            let length = ffi::webui_interface_get_size_at(window, event_number, index);
+            (ptr as *const u8, length)
+        }
+    }
+
+    fn int_at(&self, window: usize, event_number: usize, index: usize) -> i64 {
+        unsafe { ffi::webui_interface_get_int_at(window, event_number, index) }
+    }
+
+    fn bool_at(&self, window: usize, event_number: usize, index: usize) -> bool {
+        unsafe { ffi::webui_interface_get_bool_at(window, event_number, index) }
+    }
+
+    fn set_response(&self, window: usize, event_number: usize, response: &CStr) {
+        unsafe { ffi::webui_interface_set_response(window, event_number, response.as_ptr()) }
+    }
+
+    fn return_int(&self, window: usize, event_number: usize, n: i64) {
+        unsafe { ffi::webui_return_int(&mut Self::event(window, event_number), n) }
+    }
+
+    fn return_bool(&self, window: usize, event_number: usize, b: bool) {
+        unsafe { ffi::webui_return_bool(&mut Self::event(window, event_number), b) }
+    }
+
+    fn wait(&self) {
+        unsafe { ffi::webui_wait() }
+    }
+
+    fn exit(&self) {
+        unsafe { ffi::webui_exit() }
+    }
+
+    fn clean(&self) {
+        unsafe { ffi::webui_clean() }
+    }
+}
+
+static BACKEND: Lazy<RwLock<Arc<dyn Backend>>> = Lazy::new(|| RwLock::new(Arc::new(FfiBackend)));
+
+/// Replace the backend. Windows created before keep talking to the new
+/// backend, so set it before creating any.
+pub fn set_backend(backend: Arc<dyn Backend>) {
+    *BACKEND.write().unwrap_or_else(|err| err.into_inner()) = backend;
+}
+
+pub(crate) fn backend() -> Arc<dyn Backend> {
+    BACKEND
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
@@ -0,0 +1,240 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fmt::{self, Display},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{Browser, Error, ParseBrowserError, Result, WindowRef};
+
+/// Executable names looked up in `PATH`, and standard Linux install
+/// locations, by browser.
+const INSTALLS: &[(Browser, &[&str], &[&str])] = &[
+    (
+        Browser::Chrome,
+        &["google-chrome", "google-chrome-stable"],
+        &["/opt/google/chrome/chrome"],
+    ),
+    (
+        Browser::Firefox,
+        &["firefox", "firefox-esr"],
+        &[
+            "/usr/lib/firefox/firefox",
+            "/opt/firefox/firefox",
+            "/snap/bin/firefox",
+        ],
+    ),
+    (
+        Browser::Edge,
+        &["microsoft-edge", "microsoft-edge-stable"],
+        &["/opt/microsoft/msedge/msedge"],
+    ),
+    (Browser::Safari, &["safari"], &[]),
+    (
+        Browser::Chromium,
+        &["chromium", "chromium-browser"],
+        &["/usr/lib/chromium/chromium", "/snap/bin/chromium"],
+    ),
+    (
+        Browser::Opera,
+        &["opera"],
+        &["/usr/lib/x86_64-linux-gnu/opera/opera", "/snap/bin/opera"],
+    ),
+    (
+        Browser::Brave,
+        &["brave-browser", "brave"],
+        &["/opt/brave.com/brave/brave", "/snap/bin/brave"],
+    ),
+    (
+        Browser::Vivaldi,
+        &["vivaldi", "vivaldi-stable"],
+        &["/opt/vivaldi/vivaldi"],
+    ),
+    (Browser::Epic, &["epic"], &[]),
+    (
+        Browser::Yandex,
+        &["yandex-browser", "yandex-browser-stable"],
+        &["/opt/yandex/browser/yandex_browser"],
+    ),
+];
+
+impl Browser {
+    /// Browsers with an executable in `PATH` or a standard Linux install
+    /// location, in declaration order.
+    pub fn detect_installed() -> Vec<Browser> {
+        detect(env::var_os("PATH").as_deref(), true)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Browser::NoBrowser => "none",
+            Browser::AnyBrowser => "any",
+            Browser::Chrome => "chrome",
+            Browser::Firefox => "firefox",
+            Browser::Edge => "edge",
+            Browser::Safari => "safari",
+            Browser::Chromium => "chromium",
+            Browser::Opera => "opera",
+            Browser::Brave => "brave",
+            Browser::Vivaldi => "vivaldi",
+            Browser::Epic => "epic",
+            Browser::Yandex => "yandex",
+            Browser::ChromiumBased => "chromium-based",
+        }
+    }
+}
+
+fn detect(path: Option<&OsStr>, standard_locations: bool) -> Vec<Browser> {
+    let dirs: Vec<_> = path.map(env::split_paths).into_iter().flatten().collect();
+    INSTALLS
+        .iter()
+        .filter(|(_, names, paths)| {
+            let in_path = names.iter().any(|name| {
+                let file = format!("{}{}", name, env::consts::EXE_SUFFIX);
+                dirs.iter().any(|dir| is_executable(&dir.join(&file)))
+            });
+            in_path
+                || (standard_locations && paths.iter().any(|path| is_executable(Path::new(path))))
+        })
+        .map(|(browser, _, _)| *browser)
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Lowercase names, e.g. `chrome` or `chromium-based`, that
+/// [`FromStr`] parses back.
+impl Display for Browser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses names case insensitively, `_` and spaces are read as `-`, so
+/// `Chromium_Based` and `google-chrome` work too.
+impl FromStr for Browser {
+    type Err = ParseBrowserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace(['_', ' '], "-");
+        Ok(match name.as_str() {
+            "none" | "no-browser" => Browser::NoBrowser,
+            "any" | "any-browser" => Browser::AnyBrowser,
+            "chrome" | "google-chrome" => Browser::Chrome,
+            "firefox" => Browser::Firefox,
+            "edge" | "microsoft-edge" => Browser::Edge,
+            "safari" => Browser::Safari,
+            "chromium" => Browser::Chromium,
+            "opera" => Browser::Opera,
+            "brave" => Browser::Brave,
+            "vivaldi" => Browser::Vivaldi,
+            "epic" => Browser::Epic,
+            "yandex" => Browser::Yandex,
+            "chromium-based" => Browser::ChromiumBased,
+            _ => return Err(ParseBrowserError(s.to_string())),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Browser {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Browser {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl WindowRef {
+    /// Show the window in the first of `browsers` that starts, and return
+    /// it. Pass [`Browser::detect_installed`] to try every installed browser.
+    pub fn show_with_fallback(&self, content: &str, browsers: &[Browser]) -> Result<Browser> {
+        for &browser in browsers {
+            match self.show_browser(content, browser) {
+                Ok(()) => return Ok(browser),
+                Err(Error::BrowserNotFound(_)) => {
+                    log::debug!("browser {} is not available, trying the next one", browser)
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(Error::NoBrowserAvailable(browsers.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockBackend, Window};
+
+    #[test]
+    fn names_round_trip() {
+        let others = [
+            Browser::NoBrowser,
+            Browser::AnyBrowser,
+            Browser::ChromiumBased,
+        ];
+        for browser in INSTALLS.iter().map(|install| install.0).chain(others) {
+            assert_eq!(browser.to_string().parse(), Ok(browser));
+        }
+        assert_eq!("Chromium_Based".parse(), Ok(Browser::ChromiumBased));
+        assert_eq!(" google-chrome".parse(), Ok(Browser::Chrome));
+        assert!("netscape".parse::<Browser>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detect_in_path() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = env::temp_dir().join(format!("rust-webui-detect-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, mode) in [
+            ("firefox", 0o755),
+            ("brave", 0o755),
+            ("google-chrome", 0o644),
+        ] {
+            let file = dir.join(name);
+            fs::write(&file, "").unwrap();
+            fs::set_permissions(&file, fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let detected = detect(Some(dir.as_os_str()), false);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(detected, [Browser::Firefox, Browser::Brave]);
+    }
+
+    #[test]
+    fn show_with_fallback() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        mock.set_browsers(Some(&[Browser::Firefox]));
+        let shown =
+            window.show_with_fallback("<html></html>", &[Browser::Chrome, Browser::Firefox]);
+        let missing = window.show_with_fallback("<html></html>", &[Browser::Edge]);
+        mock.set_browsers(None);
+
+        assert_eq!(shown.unwrap(), Browser::Firefox);
+        assert!(window.is_shown());
+        assert!(
+            matches!(missing, Err(Error::NoBrowserAvailable(browsers)) if browsers == [Browser::Edge])
+        );
+    }
+}
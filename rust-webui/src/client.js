@@ -0,0 +1,80 @@
+// Client side of rust-webui. Windows using async or JSON bindings or events
+// run it when the page connects, load it after `webui.js` to use it earlier.
+(() => {
+    if (globalThis.rustWebui) {
+        return;
+    }
+
+    // Async bindings answer `webui.call` with a call id right away and settle
+    // it later. Results can arrive before the id, so they are kept until then,
+    // or for a minute if the call wasn't made through `callAsync`.
+    const pending = new Map();
+    const settled = new Map();
+
+    const settle = (id, ok, value) => {
+        const waiter = pending.get(id);
+        if (waiter) {
+            pending.delete(id);
+            ok ? waiter.resolve(value) : waiter.reject(new Error(value));
+        } else {
+            settled.set(id, { ok, value });
+            setTimeout(() => settled.delete(id), 60000);
+        }
+    };
+
+    // Events sent with `Window::emit`, listeners by event name.
+    const listeners = new Map();
+
+    globalThis.rustWebui = {
+        on(event, callback) {
+            if (!listeners.has(event)) {
+                listeners.set(event, new Set());
+            }
+            listeners.get(event).add(callback);
+            return () => listeners.get(event).delete(callback);
+        },
+
+        async callAsync(fn, ...args) {
+            const id = Number(await webui.call(fn, ...args));
+            return new Promise((resolve, reject) => {
+                const result = settled.get(id);
+                if (result) {
+                    settled.delete(id);
+                    result.ok ? resolve(result.value) : reject(new Error(result.value));
+                } else {
+                    pending.set(id, { resolve, reject });
+                }
+            });
+        },
+
+        // `webui.call` resolves with the envelope of a JSON binding, also for
+        // errors. This unwraps it and rejects on `Err`, the `CommandError` is
+        // the `detail` of the error.
+        async invoke(fn, ...args) {
+            const response = await webui.call(fn, ...args.map((arg) => JSON.stringify(arg)));
+            const result = JSON.parse(response);
+            if (result.t === 'Ok') {
+                return result.c;
+            }
+            const error = new Error(result.c.message);
+            error.detail = result.c;
+            throw error;
+        },
+
+        _resolve: (id, value) => settle(id, true, value),
+        _reject: (id, message) => settle(id, false, message),
+        _emit(event, payload) {
+            for (const callback of listeners.get(event) ?? []) {
+                try {
+                    callback(payload);
+                } catch (err) {
+                    console.error(`listener for "${event}" failed`, err);
+                }
+            }
+        },
+    };
+
+    if (typeof webui !== 'undefined') {
+        webui.on = rustWebui.on;
+    }
+})();
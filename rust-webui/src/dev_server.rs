@@ -0,0 +1,232 @@
+use std::{
+    io,
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Error, Result, WindowRef};
+
+/// Show a frontend dev server in debug builds and built assets in release
+/// builds:
+///
+/// ```no_run
+/// # use rust_webui::{DevServer, Window};
+/// # use std::process::Command;
+/// let mut window = Window::new();
+/// let mut pnpm = Command::new("pnpm");
+/// pnpm.arg("dev").current_dir("ui");
+/// let _dev = DevServer::new("http://localhost:3000")
+///     .command(pnpm)
+///     .release_root_folder("ui/dist")
+///     .show(&mut window)?;
+/// # Ok::<(), rust_webui::Error>(())
+/// ```
+///
+/// The page loads `webui.js` from the dev server, which has to proxy it to
+/// WebUI. The command gets the WebUI port as `WEBUI_PORT`, for Vite:
+///
+/// ```js
+/// const webui = `http://127.0.0.1:${process.env.WEBUI_PORT ?? 8080}`;
+/// server: { proxy: { "/webui.js": webui, "/_webui_ws_connect": { target: webui, ws: true } } }
+/// ```
+#[derive(Debug)]
+pub struct DevServer {
+    url: String,
+    command: Option<Command>,
+    webui_port: usize,
+    timeout: Duration,
+    entry: String,
+    release: Option<ReleaseAssets>,
+}
+
+/// What [`DevServer`] shows in release builds.
+#[derive(Debug, Clone)]
+pub enum ReleaseAssets {
+    RootFolder(PathBuf),
+    #[cfg(feature = "assets")]
+    Embedded(&'static crate::assets::Bundle),
+}
+
+/// Keeps the dev server started by [`DevServer::show`] running, it is killed
+/// on drop.
+#[derive(Debug)]
+pub struct DevSession {
+    child: Option<Child>,
+}
+
+impl Drop for DevSession {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl DevServer {
+    /// A dev server reachable at `url`, with WebUI on port 8080.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            command: None,
+            webui_port: 8080,
+            timeout: Duration::from_secs(30),
+            entry: "index.html".to_string(),
+            release: None,
+        }
+    }
+
+    /// Start the dev server with `command` if it isn't running yet.
+    pub fn command(mut self, command: Command) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// The port WebUI listens on, where the dev server proxies `webui.js` to.
+    pub fn webui_port(mut self, port: usize) -> Self {
+        self.webui_port = port;
+        self
+    }
+
+    /// How long to wait for the dev server to be reachable, 30 seconds by
+    /// default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The page shown in release builds, `index.html` by default.
+    pub fn entry(mut self, path: impl Into<String>) -> Self {
+        self.entry = path.into();
+        self
+    }
+
+    pub fn release_root_folder(mut self, path: impl Into<PathBuf>) -> Self {
+        self.release = Some(ReleaseAssets::RootFolder(path.into()));
+        self
+    }
+
+    #[cfg(feature = "assets")]
+    pub fn release_embedded(mut self, bundle: &'static crate::assets::Bundle) -> Self {
+        self.release = Some(ReleaseAssets::Embedded(bundle));
+        self
+    }
+
+    /// [`show_dev`](DevServer::show_dev) in debug builds,
+    /// [`show_release`](DevServer::show_release) otherwise.
+    pub fn show(self, window: &mut WindowRef) -> Result<DevSession> {
+        if cfg!(debug_assertions) {
+            self.show_dev(window)
+        } else {
+            self.show_release(window)?;
+            Ok(DevSession { child: None })
+        }
+    }
+
+    /// Start the dev server if needed, wait until it is reachable and show
+    /// it.
+    pub fn show_dev(mut self, window: &mut WindowRef) -> Result<DevSession> {
+        window.set_port(self.webui_port)?;
+
+        let addr = socket_addr(&self.url)?;
+        let mut session = DevSession { child: None };
+        if !reachable(&addr) {
+            if let Some(command) = &mut self.command {
+                command.env("WEBUI_PORT", self.webui_port.to_string());
+                session.child = Some(command.spawn().map_err(Error::Io)?);
+            }
+            let deadline = Instant::now() + self.timeout;
+            while !reachable(&addr) {
+                if let Some(status) = session
+                    .child
+                    .as_mut()
+                    .and_then(|child| child.try_wait().ok().flatten())
+                {
+                    return Err(Error::Io(io::Error::other(format!(
+                        "dev server exited with {}",
+                        status
+                    ))));
+                }
+                if Instant::now() >= deadline {
+                    return Err(Error::Io(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("dev server at {} is not reachable", self.url),
+                    )));
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        window.show(&self.url)?;
+        Ok(session)
+    }
+
+    /// Show the release assets.
+    pub fn show_release(self, window: &mut WindowRef) -> Result<()> {
+        match self.release {
+            Some(ReleaseAssets::RootFolder(path)) => window.set_root_folder(path)?,
+            #[cfg(feature = "assets")]
+            Some(ReleaseAssets::Embedded(bundle)) => window.serve_embedded(bundle)?,
+            None => return Err(Error::InvalidConfig("no release assets configured")),
+        }
+        window.show(&self.entry)
+    }
+}
+
+/// `host:port` of an `http://` url.
+fn socket_addr(url: &str) -> Result<String> {
+    let invalid = || {
+        Error::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid dev server url {}", url),
+        ))
+    };
+    let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+    let host = rest
+        .split('/')
+        .next()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(invalid)?;
+    if host
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+    {
+        Ok(host.to_string())
+    } else {
+        Ok(format!("{}:80", host))
+    }
+}
+
+fn reachable(addr: &str) -> bool {
+    let Ok(addrs) = addr.to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_addrs() {
+        assert_eq!(
+            socket_addr("http://localhost:3000").unwrap(),
+            "localhost:3000"
+        );
+        assert_eq!(
+            socket_addr("http://localhost:3000/app/").unwrap(),
+            "localhost:3000"
+        );
+        assert_eq!(
+            socket_addr("http://example.com/").unwrap(),
+            "example.com:80"
+        );
+        assert!(socket_addr("localhost:3000").is_err());
+    }
+}
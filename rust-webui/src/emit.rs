@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::{
+    hooks::install_client, webui::json_string, webui::live_windows, Error, Result, WindowRef,
+};
+
+/// Held while a message is handed to WebUI, so events reach each page in the
+/// order `emit` was called.
+static EMIT_LOCK: Mutex<()> = Mutex::new(());
+
+impl WindowRef {
+    /// Send an event to the page, where it is received by listeners
+    /// registered with the rust-webui client, see
+    /// [`CLIENT_JS`](crate::CLIENT_JS):
+    ///
+    /// ```js
+    /// const off = webui.on("progress", ({ done, total }) => { ... });
+    /// ```
+    ///
+    /// The first emit runs the client in the window, and it runs again
+    /// whenever a page connects. Events to the same window are delivered in
+    /// the order they are emitted.
+    pub fn emit<T: Serialize + ?Sized>(&self, event: &str, payload: &T) -> Result<()> {
+        let script = emit_script(event, payload)?;
+        let _guard = EMIT_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        install_client(self)?;
+        self.run(&script)
+    }
+}
+
+/// Send an event to every shown window created through rust-webui.
+pub fn broadcast<T: Serialize + ?Sized>(event: &str, payload: &T) -> Result<()> {
+    let script = emit_script(event, payload)?;
+    let _guard = EMIT_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    for window in live_windows() {
+        match install_client(&window).and_then(|()| window.run(&script)) {
+            Ok(()) | Err(Error::WindowNotShown) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+fn emit_script<T: Serialize + ?Sized>(event: &str, payload: &T) -> Result<String> {
+    let payload = serde_json::to_string(payload).map_err(Error::Json)?;
+    Ok(format!(
+        "rustWebui._emit({}, {});",
+        json_string(event),
+        payload
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock::MockBackend, EventType, Window, CLIENT_JS};
+
+    #[test]
+    fn emit_in_order() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        assert!(window.emit("step", &1).is_err());
+
+        // The client runs when the page connects.
+        window.show("<html></html>").unwrap();
+        mock.fire(&window, EventType::Connected, "", &[]);
+        for step in 1..=3 {
+            window.emit("step", &step).unwrap();
+        }
+        let runs: Vec<_> = mock
+            .runs(&window)
+            .into_iter()
+            .filter(|run| run == CLIENT_JS || run.contains(r#"("step""#))
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                CLIENT_JS,
+                r#"rustWebui._emit("step", 1);"#,
+                r#"rustWebui._emit("step", 2);"#,
+                r#"rustWebui._emit("step", 3);"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn broadcast_to_shown_windows() {
+        let mock = MockBackend::install();
+        let shown = [Window::new(), Window::new()];
+        let hidden = Window::new();
+        for window in &shown {
+            window.show("<html></html>").unwrap();
+        }
+
+        super::broadcast("log", "hello").unwrap();
+        let message = r#"rustWebui._emit("log", "hello");"#.to_string();
+        for window in &shown {
+            assert!(mock.runs(window).contains(&message));
+        }
+        assert!(mock.runs(&hidden).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    ffi::NulError,
+    fmt::{self, Display},
+    ops::RangeInclusive,
+    path::PathBuf,
+    str::Utf8Error,
+};
+
+use crate::Browser;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A string passed to WebUI contains an interior NUL byte.
+    InteriorNul(NulError),
+    /// A path cannot be passed to WebUI because it is not valid UTF-8.
+    NonUtf8Path(PathBuf),
+    /// The requested port is already in use.
+    PortInUse(usize),
+    /// Every port in the [`App`](crate::App) port range is in use.
+    PortsExhausted(RangeInclusive<usize>),
+    /// The requested browser is not installed or failed to start.
+    BrowserNotFound(Browser),
+    /// None of the browsers passed to
+    /// [`show_with_fallback`](crate::WindowRef::show_with_fallback) started.
+    NoBrowserAvailable(Vec<Browser>),
+    /// WebUI failed to show the window.
+    ShowFailed,
+    /// The operation needs a window that is currently shown.
+    WindowNotShown,
+    /// The root folder does not exist or the window is already shown.
+    InvalidRootFolder(PathBuf),
+    /// The [`App`](crate::App) settings are invalid.
+    InvalidConfig(&'static str),
+    /// The window number is above what WebUI supports for this operation.
+    WindowLimit(usize),
+    Io(std::io::Error),
+    /// WebUI rejected the TLS certificate or private key.
+    #[cfg(feature = "tls")]
+    InvalidCertificate,
+    /// An async binding was created outside of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    NoRuntime,
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    Script(ScriptError),
+    Arg(ArgError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InteriorNul(err) => write!(f, "string contains an interior nul byte: {}", err),
+            Error::NonUtf8Path(path) => write!(f, "path is not valid utf8: {}", path.display()),
+            Error::PortInUse(port) => write!(f, "port {} is already in use", port),
+            Error::PortsExhausted(ports) => write!(
+                f,
+                "every port from {} to {} is in use",
+                ports.start(),
+                ports.end()
+            ),
+            Error::BrowserNotFound(browser) => write!(f, "browser {} is not available", browser),
+            Error::NoBrowserAvailable(browsers) if browsers.is_empty() => {
+                f.write_str("no browser to show the window in")
+            }
+            Error::NoBrowserAvailable(browsers) => {
+                let names: Vec<String> = browsers.iter().map(Browser::to_string).collect();
+                write!(f, "none of the browsers {} is available", names.join(", "))
+            }
+            Error::ShowFailed => f.write_str("failed to show the window"),
+            Error::WindowNotShown => f.write_str("window is not shown"),
+            Error::InvalidRootFolder(path) => {
+                write!(f, "cannot use {} as root folder", path.display())
+            }
+            Error::InvalidConfig(message) => write!(f, "invalid app config: {}", message),
+            Error::WindowLimit(window) => {
+                write!(f, "window number {} is above the supported maximum", window)
+            }
+            Error::Io(err) => write!(f, "io error: {}", err),
+            #[cfg(feature = "tls")]
+            Error::InvalidCertificate => f.write_str("invalid tls certificate or private key"),
+            #[cfg(feature = "tokio")]
+            Error::NoRuntime => f.write_str("no tokio runtime is running"),
+            #[cfg(feature = "serde")]
+            Error::Json(err) => write!(f, "json error: {}", err),
+            Error::Script(err) => Display::fmt(err, f),
+            Error::Arg(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InteriorNul(err) => Some(err),
+            Error::Io(err) => Some(err),
+            #[cfg(feature = "serde")]
+            Error::Json(err) => Some(err),
+            Error::Script(err) => Some(err),
+            Error::Arg(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(value: NulError) -> Self {
+        Error::InteriorNul(value)
+    }
+}
+
+impl From<ScriptError> for Error {
+    fn from(value: ScriptError) -> Self {
+        Error::Script(value)
+    }
+}
+
+impl From<ArgError> for Error {
+    fn from(value: ArgError) -> Self {
+        Error::Arg(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The script contains an interior NUL byte.
+    InteriorNul(NulError),
+    /// The window is not shown, so there is no page to run the script in.
+    WindowNotShown,
+    /// The script threw, the message is the stringified exception.
+    Exception(String),
+    /// No response arrived before the timeout.
+    Timeout,
+    /// The response did not fit in the maximum buffer size.
+    ResponseTooLarge,
+    /// The response is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::InteriorNul(err) => {
+                write!(f, "script contains an interior nul byte: {}", err)
+            }
+            ScriptError::WindowNotShown => f.write_str("window is not shown"),
+            ScriptError::Exception(message) => write!(f, "script threw: {}", message),
+            ScriptError::Timeout => f.write_str("script timed out"),
+            ScriptError::ResponseTooLarge => f.write_str("script response is too large"),
+            ScriptError::InvalidUtf8(err) => write!(f, "script response is not utf8: {}", err),
+            #[cfg(feature = "serde")]
+            ScriptError::Json(err) => write!(f, "invalid script response: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScriptError::InteriorNul(err) => Some(err),
+            ScriptError::InvalidUtf8(err) => Some(err),
+            #[cfg(feature = "serde")]
+            ScriptError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for ScriptError {
+    fn from(value: NulError) -> Self {
+        ScriptError::InteriorNul(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum ArgError {
+    /// The argument at this index is missing or empty.
+    Missing(usize),
+    /// The argument is not valid UTF-8.
+    InvalidUtf8(usize, Utf8Error),
+    /// The argument could not be parsed as the expected type.
+    Invalid {
+        index: usize,
+        expected: &'static str,
+    },
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::Missing(index) => write!(f, "argument {} is missing", index),
+            ArgError::InvalidUtf8(index, err) => {
+                write!(f, "argument {} is not utf8: {}", index, err)
+            }
+            ArgError::Invalid { index, expected } => {
+                write!(f, "argument {} is not a valid {}", index, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ArgError::InvalidUtf8(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The string passed to [`Browser::from_str`](std::str::FromStr::from_str) is
+/// not a browser name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBrowserError(pub(crate) String);
+
+impl Display for ParseBrowserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown browser {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBrowserError {}
@@ -0,0 +1,286 @@
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_int, c_void, CStr},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::{Arc, RwLock},
+};
+
+use webui_sys as ffi;
+
+use crate::{backend::backend, webui::panic_message, Error, Result, WindowRef};
+
+type FileHandlerMap = HashMap<usize, Arc<dyn Fn(&str) -> Option<Response> + Send + Sync>>;
+
+static FILE_HANDLERS: Lazy<RwLock<FileHandlerMap>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub(crate) type RawFileHandler = unsafe extern "C" fn(*const c_char, *mut c_int) -> *const c_void;
+
+/// WebUI's `WEBUI_MAX_IDS`, window numbers are always below this.
+const MAX_WINDOWS: usize = 256;
+
+// WebUI does not pass the window number to the file handler, so every window
+// number gets its own monomorphized trampoline.
+macro_rules! trampolines {
+    ($($hi:literal)*; $lo:tt) => {
+        [$(trampolines!(@row $hi; $lo)),*]
+    };
+    (@row $hi:literal; ($($lo:literal)*)) => {
+        [$(file_handler::<{ $hi * 16 + $lo }> as RawFileHandler),*]
+    };
+}
+
+static TRAMPOLINES: [[RawFileHandler; 16]; 16] = trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15;
+    (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+);
+
+unsafe extern "C" fn file_handler<const WINDOW: usize>(
+    filename: *const c_char,
+    length: *mut c_int,
+) -> *const c_void {
+    let Ok(filename) = CStr::from_ptr(filename).to_str() else {
+        return ptr::null();
+    };
+    match serve(WINDOW, filename) {
+        Some(response) => response.into_raw(length),
+        None => ptr::null(),
+    }
+}
+
+/// The `webui_set_file_handler` callback for `window`.
+pub(crate) fn raw_file_handler(window: usize) -> RawFileHandler {
+    TRAMPOLINES[window / 16][window % 16]
+}
+
+/// Run the file handler of `window` for `filename`, `None` if it has none.
+pub(crate) fn serve(window: usize, filename: &str) -> Option<Response> {
+    let handler = {
+        let handlers = FILE_HANDLERS.read().unwrap_or_else(|err| err.into_inner());
+        handlers.get(&window)?.clone()
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| handler(filename))) {
+        Ok(response) => response,
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            log::error!("file handler panicked serving {:?}: {}", filename, message);
+            Some(Response::new("500 Internal Server Error").with_status(500))
+        }
+    }
+}
+
+// Handlers are dropped after unlocking, they may own a window.
+pub(crate) fn remove_file_handler(window: usize) {
+    let removed = FILE_HANDLERS.write().unwrap().remove(&window);
+    drop(removed);
+}
+
+pub(crate) fn remove_all_file_handlers() {
+    let removed = std::mem::take(&mut *FILE_HANDLERS.write().unwrap());
+    drop(removed);
+}
+
+impl WindowRef {
+    /// Serve files from Rust. The handler receives the request path, e.g.
+    /// `/index.html`, and returning `None` lets WebUI fall back to the root
+    /// folder.
+    pub fn set_file_handler(
+        &mut self,
+        handler: impl Fn(&str) -> Option<Response> + Send + Sync + 'static,
+    ) -> Result<()> {
+        let window = self.handle();
+        if window >= MAX_WINDOWS {
+            return Err(Error::WindowLimit(window));
+        }
+
+        {
+            let mut handlers = FILE_HANDLERS.write().unwrap();
+            handlers.insert(window, Arc::new(handler));
+        }
+        backend().set_file_handler(window);
+        Ok(())
+    }
+}
+
+/// A full HTTP response returned from a file handler.
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// A `200 OK` response with `application/octet-stream` content.
+    pub fn new(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/octet-stream".to_string(),
+            )],
+            body: body.into(),
+        }
+    }
+
+    /// A `200 OK` response with the content type guessed from `path`.
+    pub fn from_path(path: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(body).with_header("Content-Type", mime_type(path))
+    }
+
+    pub fn not_found() -> Self {
+        Self::new("404 Not Found")
+            .with_status(404)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Set a header, replacing any existing header with the same name.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Serialize as a raw HTTP/1.1 response, headers and body.
+    pub fn to_http(&self) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                continue;
+            }
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", self.body.len()));
+
+        let mut out = head.into_bytes();
+        out.extend_from_slice(&self.body);
+        out
+    }
+
+    /// Copy into a `webui_malloc` buffer, which WebUI frees after sending.
+    unsafe fn into_raw(self, length: *mut c_int) -> *const c_void {
+        let http = self.to_http();
+        let Ok(len) = c_int::try_from(http.len()) else {
+            return ptr::null();
+        };
+        let buf = ffi::webui_malloc(http.len());
+        if buf.is_null() {
+            return ptr::null();
+        }
+        ptr::copy_nonoverlapping(http.as_ptr(), buf as *mut u8, http.len());
+        *length = len;
+        buf
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+/// Guess the MIME type from the file extension of `path`.
+pub fn mime_type(path: &str) -> &'static str {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    let extension = match path.rsplit_once('.') {
+        Some((_, ext)) if !ext.contains('/') => ext.to_ascii_lowercase(),
+        _ => return "application/octet-stream",
+    };
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockBackend, Window};
+
+    #[test]
+    fn serve_files() {
+        let mock = MockBackend::install();
+        let mut window = Window::new();
+        assert!(mock.request(&window, "/index.html").is_none());
+
+        window
+            .set_file_handler(|path| match path {
+                "/index.html" => Some(Response::new("<html></html>")),
+                "/panic" => panic!("oops"),
+                _ => None,
+            })
+            .unwrap();
+        let response = mock.request(&window, "/index.html").unwrap();
+        assert_eq!(response.body(), b"<html></html>");
+        assert!(mock.request(&window, "/missing").is_none());
+        assert_eq!(mock.request(&window, "/panic").unwrap().status(), 500);
+
+        let handle = window.handle();
+        drop(window);
+        assert!(serve(handle, "/index.html").is_none());
+    }
+
+    #[test]
+    fn response_to_http() {
+        let response = Response::from_path("/assets/index.JS?v=1", "let a = 1;");
+        assert_eq!(
+            response.to_http(),
+            b"HTTP/1.1 200 OK\r\n\
+              Content-Type: text/javascript; charset=utf-8\r\n\
+              Content-Length: 10\r\n\r\n\
+              let a = 1;"
+        );
+    }
+}
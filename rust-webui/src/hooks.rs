@@ -0,0 +1,240 @@
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use crate::{backend::backend, webui::panic_message, Binding, Event, EventType, Result, WindowRef};
+
+struct HookFn {
+    id: u64,
+    event_type: EventType,
+    func: Arc<dyn Fn(&mut Event) + Send + Sync>,
+}
+
+/// Hooks by window. WebUI only sends connect, disconnect and navigation events
+/// to the `""` binding, so adding a hook binds it and the event handler runs
+/// the hooks next to whatever closure is bound to `""`.
+static HOOKS: Lazy<RwLock<HashMap<usize, Vec<HookFn>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+static NEXT_HOOK_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A closure added with [`on_connect`](WindowRef::on_connect),
+/// [`on_disconnect`](WindowRef::on_disconnect) or
+/// [`on_navigate`](WindowRef::on_navigate).
+#[derive(Debug)]
+pub struct Hook {
+    window: usize,
+    id: u64,
+}
+
+impl Hook {
+    /// Remove the closure. Returns `false` if it was already removed.
+    pub fn remove(self) -> bool {
+        let removed = {
+            let mut hooks = HOOKS.write().unwrap();
+            let Some(window_hooks) = hooks.get_mut(&self.window) else {
+                return false;
+            };
+            let index = window_hooks.iter().position(|hook| hook.id == self.id);
+            index.map(|index| window_hooks.remove(index))
+        };
+        // Dropped after unlocking, the closure may own a window.
+        removed.is_some()
+    }
+}
+
+impl WindowRef {
+    /// Called when the page connects, and again after every reconnect, e.g.
+    /// after a reload.
+    pub fn on_connect(&self, func: impl Fn(&mut Event) + Send + Sync + 'static) -> Result<Hook> {
+        self.add_hook(EventType::Connected, func)
+    }
+
+    /// Called when the page disconnects, e.g. because the tab was closed.
+    pub fn on_disconnect(&self, func: impl Fn(&mut Event) + Send + Sync + 'static) -> Result<Hook> {
+        self.add_hook(EventType::Disconnected, func)
+    }
+
+    /// Called with the new url when the page navigates.
+    pub fn on_navigate(
+        &self,
+        func: impl Fn(&mut Event, &str) + Send + Sync + 'static,
+    ) -> Result<Hook> {
+        self.add_hook(EventType::Navigation, move |event| {
+            let url = event.get_string_at(0).into_owned();
+            func(event, &url)
+        })
+    }
+
+    /// Called when the element with id `element` is clicked. This binds the
+    /// element, replacing a closure bound to it with [`bind`](WindowRef::bind).
+    pub fn on_click(
+        &self,
+        element: &str,
+        func: impl Fn(&mut Event) + Send + Sync + 'static,
+    ) -> Result<Binding> {
+        self.bind(element, move |event| {
+            if event.event_type == EventType::MouseClick {
+                func(event)
+            }
+        })
+    }
+
+    fn add_hook(
+        &self,
+        event_type: EventType,
+        func: impl Fn(&mut Event) + Send + Sync + 'static,
+    ) -> Result<Hook> {
+        // Binding `""` again is a no-op for WebUI, and leaves a closure bound
+        // to it in place.
+        backend().bind(self.handle(), c"");
+        let id = NEXT_HOOK_ID.fetch_add(1, Ordering::Relaxed);
+        HOOKS
+            .write()
+            .unwrap()
+            .entry(self.handle())
+            .or_default()
+            .push(HookFn {
+                id,
+                event_type,
+                func: Arc::new(func),
+            });
+        Ok(Hook {
+            window: self.handle(),
+            id,
+        })
+    }
+}
+
+/// Run [`CLIENT_JS`](crate::CLIENT_JS) in the window now if it is shown, and
+/// again whenever a page connects.
+#[cfg(any(feature = "serde", feature = "tokio", feature = "async-std"))]
+pub(crate) fn install_client(window: &WindowRef) -> Result<()> {
+    struct ClientInstalled;
+
+    if !window.manage(ClientInstalled) {
+        return Ok(());
+    }
+    window.on_connect(|event| {
+        if let Err(err) = event.window.run(crate::CLIENT_JS) {
+            log::warn!("failed to install the rust-webui client: {}", err);
+        }
+    })?;
+    if window.is_shown() {
+        window.run(crate::CLIENT_JS)?;
+    }
+    Ok(())
+}
+
+/// Run the hooks for the event, returns `false` if there are none.
+pub(crate) fn dispatch(event: &mut Event) -> bool {
+    // Don't hold the lock while the hooks run, so they can add hooks.
+    let funcs: Vec<_> = {
+        let hooks = HOOKS.read().unwrap_or_else(|err| err.into_inner());
+        let Some(window_hooks) = hooks.get(&event.window.handle()) else {
+            return false;
+        };
+        window_hooks
+            .iter()
+            .filter(|hook| hook.event_type == event.event_type)
+            .map(|hook| hook.func.clone())
+            .collect()
+    };
+    for func in &funcs {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| func(event))) {
+            let message = panic_message(payload.as_ref());
+            log::error!("{:?} hook panicked: {}", event.event_type, message);
+        }
+    }
+    !funcs.is_empty()
+}
+
+pub(crate) fn remove_hooks(window: usize) {
+    let removed = HOOKS.write().unwrap().remove(&window);
+    drop(removed);
+}
+
+pub(crate) fn remove_all_hooks() {
+    let removed = std::mem::take(&mut *HOOKS.write().unwrap());
+    drop(removed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{mock::MockBackend, EventType, Window};
+
+    #[test]
+    fn hooks_run_next_to_bind_all() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        let all = Arc::new(AtomicUsize::new(0));
+        let connects = Arc::new(AtomicUsize::new(0));
+
+        let counter = all.clone();
+        window
+            .bind("", move |_| counter.fetch_add(1, Ordering::Relaxed))
+            .unwrap();
+        let counter = connects.clone();
+        window
+            .on_connect(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+        mock.fire(&window, EventType::Connected, "", &[]);
+
+        // Binding `""` after adding a hook keeps the hook too.
+        let counter = all.clone();
+        window
+            .bind("", move |_| counter.fetch_add(10, Ordering::Relaxed))
+            .unwrap();
+        mock.fire(&window, EventType::Connected, "", &[]);
+
+        assert_eq!(all.load(Ordering::Relaxed), 11);
+        assert_eq!(connects.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn closures_owning_windows_are_dropped_unlocked() {
+        let _mock = MockBackend::install();
+        let window = Window::new();
+        let inner = Window::new();
+        window.bind("owner", move |_| inner.handle()).unwrap();
+        let inner = Window::new();
+        window
+            .on_connect(move |_| log::debug!("{:?}", inner))
+            .unwrap();
+        // Dropping `window` drops the closures, which drop their windows.
+        drop(window);
+    }
+
+    #[test]
+    fn hooks_and_state() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window.manage(AtomicUsize::new(0));
+        window
+            .on_connect(|event| {
+                event.state::<AtomicUsize>().fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+        window
+            .bind("count", |event| {
+                event.state::<AtomicUsize>().load(Ordering::Relaxed) as u32
+            })
+            .unwrap();
+
+        mock.fire(&window, EventType::Connected, "", &[]);
+        mock.fire(&window, EventType::Connected, "", &[]);
+        assert_eq!(mock.call(&window, "count", &[]).as_deref(), Some("2"));
+    }
+}
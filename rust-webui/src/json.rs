@@ -0,0 +1,358 @@
+//! Typed JSON bindings.
+//!
+//! Every argument passed from JS is expected to be a JSON string, i.e. the
+//! page calls `webui.call("add", JSON.stringify(x), JSON.stringify(y))`, and
+//! argument `i` of the Rust handler is deserialized from `get_str_at(i)`.
+//!
+//! The response is always a JSON envelope:
+//!
+//! ```text
+//! { "t": "Ok",  "c": <output> }
+//! { "t": "Err", "c": { "kind": "invalid_argument" | "handler" | "internal" | "panic",
+//!                      "message": "...",
+//!                      "index": 0,      // only for invalid_argument
+//!                      "data": <any> }  // optional, set by the handler
+//! }
+//! ```
+//!
+//! `webui.call` never rejects, an `Err` envelope resolves like any other
+//! response. Pages should call the handlers through the TypeScript client
+//! generated with the `typescript` feature, or through `rustWebui.invoke`
+//! from [`CLIENT_JS`](crate::CLIENT_JS), which is run in windows with JSON
+//! bindings:
+//!
+//! ```js
+//! try {
+//!     const sum = await rustWebui.invoke("add", 1, 2);
+//! } catch (err) {
+//!     console.error(err.detail.kind, err.message);
+//! }
+//! ```
+//!
+//! Both serialize the arguments, unwrap the envelope and reject on `Err`.
+//!
+//! Handlers are closures passed to [`WindowRef::bind_json`], or functions
+//! marked with [`command`] and bound with [`WindowRef::bind_command`]. Their
+//! error type can be [`CommandError`] or anything that implements `Display`,
+//! e.g. `anyhow::Error` or `String`.
+
+use std::fmt::Display;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{hooks::install_client, Binding, Event, IntoResponse, Result, WindowRef};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum Envelope<T> {
+    Ok(T),
+    Err(CommandError),
+}
+
+impl<T> From<Result<T, CommandError>> for Envelope<T> {
+    fn from(value: Result<T, CommandError>) -> Self {
+        match value {
+            Ok(v) => Envelope::Ok(v),
+            Err(err) => Envelope::Err(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandErrorKind {
+    /// An argument was missing or could not be deserialized.
+    InvalidArgument,
+    /// The handler returned an error.
+    Handler,
+    /// The output could not be serialized.
+    Internal,
+    /// The handler panicked, see [`crate::set_panic_handler`].
+    Panic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandError {
+    pub kind: CommandErrorKind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl CommandError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            kind: CommandErrorKind::Handler,
+            message: message.into(),
+            index: None,
+            data: None,
+        }
+    }
+
+    /// Attach extra data for the JS side, e.g. validation details.
+    pub fn with_data(mut self, data: impl Serialize) -> Self {
+        self.data = serde_json::to_value(data).ok();
+        self
+    }
+
+    fn invalid_argument(index: usize, message: impl Into<String>) -> Self {
+        Self {
+            kind: CommandErrorKind::InvalidArgument,
+            message: message.into(),
+            index: Some(index),
+            data: None,
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            kind: CommandErrorKind::Internal,
+            message: message.into(),
+            index: None,
+            data: None,
+        }
+    }
+}
+
+/// Error type of a JSON handler. Errors other than [`CommandError`] become a
+/// [`CommandErrorKind::Handler`] error with their `Display` output.
+///
+/// `CommandError` doesn't implement `Display` itself, so its kind and data
+/// are kept.
+pub trait IntoCommandError {
+    fn into_command_error(self) -> CommandError;
+}
+
+impl IntoCommandError for CommandError {
+    fn into_command_error(self) -> CommandError {
+        self
+    }
+}
+
+impl<E: Display> IntoCommandError for E {
+    fn into_command_error(self) -> CommandError {
+        CommandError::new(self.to_string())
+    }
+}
+
+/// Return type of a JSON handler.
+pub trait CommandResult {
+    type Output: Serialize;
+
+    fn into_result(self) -> Result<Self::Output, CommandError>;
+}
+
+impl<O, E> CommandResult for Result<O, E>
+where
+    O: Serialize,
+    E: IntoCommandError,
+{
+    type Output = O;
+
+    fn into_result(self) -> Result<O, CommandError> {
+        self.map_err(IntoCommandError::into_command_error)
+    }
+}
+
+/// A function marked with [`command`], the attribute implements this.
+pub trait Command {
+    /// The name the function is bound to.
+    const NAME: &'static str;
+    type Args;
+    type Handler: JsonHandler<Self::Args>;
+
+    fn handler() -> Self::Handler;
+}
+
+pub use rust_webui_macros::command;
+
+/// Respond with `T` serialized as plain JSON, without the envelope.
+///
+/// ```ignore
+/// window.bind("config", |_| Json(config.clone()))?;
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self, event: &mut Event) {
+        if let Err(err) = event.return_json(&self.0) {
+            log::error!("cannot respond to {:?}: {}", event.element, err);
+        }
+    }
+}
+
+/// Implemented for `Fn(A, B, ..) -> Result<O, E>` with up to 6 arguments,
+/// where every argument is `DeserializeOwned`.
+pub trait JsonHandler<Args>: Send + Sync + 'static {
+    fn handle(&self, event: &Event) -> String;
+}
+
+fn arg_at<T: DeserializeOwned>(event: &Event, index: usize) -> Result<T, CommandError> {
+    let input = event
+        .get_str_at(index)
+        .map_err(|err| CommandError::invalid_argument(index, err.to_string()))?;
+    if input.is_empty() {
+        return Err(CommandError::invalid_argument(index, "missing argument"));
+    }
+    serde_json::from_str(input)
+        .map_err(|err| CommandError::invalid_argument(index, err.to_string()))
+}
+
+fn encode<T: Serialize>(result: Result<T, CommandError>) -> String {
+    serde_json::to_string(&Envelope::from(result)).unwrap_or_else(|err| {
+        let envelope = Envelope::<()>::Err(CommandError::internal(err.to_string()));
+        serde_json::to_string(&envelope).expect("error envelope is always serializable")
+    })
+}
+
+macro_rules! impl_json_handler {
+    ($($arg:ident => $index:tt),*) => {
+        impl<F, R, $($arg,)*> JsonHandler<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> R + Send + Sync + 'static,
+            R: CommandResult,
+            $($arg: DeserializeOwned,)*
+        {
+            #[allow(non_snake_case, unused_variables)]
+            fn handle(&self, event: &Event) -> String {
+                let call = || {
+                    $(let $arg = arg_at::<$arg>(event, $index)?;)*
+                    self($($arg),*).into_result()
+                };
+                encode(call())
+            }
+        }
+    };
+}
+
+impl_json_handler!();
+impl_json_handler!(A0 => 0);
+impl_json_handler!(A0 => 0, A1 => 1);
+impl_json_handler!(A0 => 0, A1 => 1, A2 => 2);
+impl_json_handler!(A0 => 0, A1 => 1, A2 => 2, A3 => 3);
+impl_json_handler!(A0 => 0, A1 => 1, A2 => 2, A3 => 3, A4 => 4);
+impl_json_handler!(A0 => 0, A1 => 1, A2 => 2, A3 => 3, A4 => 4, A5 => 5);
+
+impl WindowRef {
+    /// Bind a typed handler. See the [module docs](self) for the wire format.
+    ///
+    /// ```no_run
+    /// # use rust_webui::{Window, json::CommandError};
+    /// let window = Window::new();
+    /// window.bind_json("add", |x: f64, y: f64| Ok::<_, CommandError>(x + y))?;
+    /// # Ok::<(), rust_webui::Error>(())
+    /// ```
+    pub fn bind_json<Args>(
+        &self,
+        element: &str,
+        handler: impl JsonHandler<Args>,
+    ) -> Result<Binding> {
+        install_client(self)?;
+        self.bind(element, move |event| handler.handle(event))
+    }
+
+    /// Bind a function marked with [`command`] to its name.
+    ///
+    /// ```no_run
+    /// # use rust_webui::{command, Window, json::CommandError};
+    /// #[command]
+    /// fn add(x: f64, y: f64) -> Result<f64, CommandError> {
+    ///     Ok(x + y)
+    /// }
+    ///
+    /// let window = Window::new();
+    /// window.bind_command::<add>()?;
+    /// # Ok::<(), rust_webui::Error>(())
+    /// ```
+    pub fn bind_command<C: Command>(&self) -> Result<Binding> {
+        self.bind_json(C::NAME, C::handler())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_wire_format() {
+        let ok = encode::<f64>(Ok(3.0));
+        assert_eq!(ok, r#"{"t":"Ok","c":3.0}"#);
+
+        let err = encode::<()>(Err(CommandError::invalid_argument(1, "missing argument")));
+        assert_eq!(
+            err,
+            r#"{"t":"Err","c":{"kind":"invalid_argument","message":"missing argument","index":1}}"#
+        );
+    }
+
+    #[test]
+    fn display_errors() {
+        let result: Result<(), anyhow::Error> = Err(anyhow::anyhow!("no such user"));
+        let err = result.into_result().unwrap_err();
+        assert_eq!(err.kind, CommandErrorKind::Handler);
+        assert_eq!(err.message, "no such user");
+
+        let result: Result<(), CommandError> = Err(CommandError::new("x").with_data(1));
+        assert_eq!(result.into_result().unwrap_err().data, Some(1.into()));
+    }
+
+    #[crate::command(rename = "sum")]
+    fn add(x: i32, y: i32) -> Result<i32, String> {
+        x.checked_add(y).ok_or_else(|| "overflow".to_string())
+    }
+
+    #[test]
+    fn command_attribute() {
+        assert_eq!(<add as Command>::NAME, "sum");
+        assert_eq!(add(1, 2), Ok(3));
+
+        let mock = crate::mock::MockBackend::install();
+        let window = crate::Window::new();
+        window.bind_command::<add>().unwrap();
+        assert_eq!(
+            mock.call(&window, "sum", &["1", "2"]).as_deref(),
+            Some(r#"{"t":"Ok","c":3}"#)
+        );
+        let max = i32::MAX.to_string();
+        assert_eq!(
+            mock.call(&window, "sum", &[&max, "1"]).as_deref(),
+            Some(r#"{"t":"Err","c":{"kind":"handler","message":"overflow"}}"#)
+        );
+    }
+
+    #[test]
+    fn json_binding() {
+        let mock = crate::mock::MockBackend::install();
+        let window = crate::Window::new();
+        window
+            .bind_json("add", |x: f64, y: f64| Ok::<_, CommandError>(x + y))
+            .unwrap();
+
+        assert_eq!(
+            mock.call(&window, "add", &["1.5", "2"]).as_deref(),
+            Some(r#"{"t":"Ok","c":3.5}"#)
+        );
+        let response = mock.call(&window, "add", &["1"]).unwrap();
+        assert!(
+            response.contains(r#""kind":"invalid_argument""#),
+            "{}",
+            response
+        );
+    }
+
+    #[test]
+    fn json_bindings_install_the_client() {
+        let mock = crate::mock::MockBackend::install();
+        let window = crate::Window::new();
+        window.show("<html></html>").unwrap();
+        window.bind_json("a", || Ok::<_, CommandError>(1)).unwrap();
+        window.bind_json("b", || Ok::<_, CommandError>(2)).unwrap();
+        assert_eq!(mock.runs(&window), [crate::CLIENT_JS]);
+
+        mock.fire(&window, crate::EventType::Connected, "", &[]);
+        assert_eq!(mock.runs(&window), [crate::CLIENT_JS; 2]);
+    }
+}
@@ -1,13 +1,58 @@
+mod app;
+mod arg;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+mod async_bind;
+mod browser;
+mod dev_server;
+#[cfg(feature = "serde")]
+mod emit;
+mod error;
+mod file_handler;
+mod hooks;
+mod manager;
+mod profile;
+mod response;
+mod script;
+mod state;
 mod webui;
 
+#[cfg(feature = "assets")]
+pub mod assets;
+pub mod backend;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+#[cfg(feature = "test-client")]
+pub mod test_client;
+#[cfg(feature = "typescript")]
+pub mod ts;
+
+pub use app::{App, AppBuilder};
+pub use arg::FromArg;
+pub use backend::{set_backend, Backend};
+pub use dev_server::{DevServer, DevSession, ReleaseAssets};
+pub use error::{ArgError, Error, ParseBrowserError, Result, ScriptError};
+pub use file_handler::{mime_type, Response};
+pub use hooks::Hook;
+pub use manager::WindowManager;
+pub use profile::{delete_all_profiles, Profile};
+pub use response::IntoResponse;
 pub use webui::*;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(feature = "assets")]
+pub use assets::include_assets;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub use async_bind::wait_async;
+#[cfg(feature = "serde")]
+pub use emit::broadcast;
+#[cfg(feature = "serde")]
+pub use json::command;
+
+// Lets the proc macros refer to `::rust_webui` inside this crate too.
+extern crate self as rust_webui;
 
-    #[test]
-    fn it_works() {
-       
-    }
-}
+/// The rust-webui page client, needed for async bindings, events and calling
+/// JSON bindings with `rustWebui.invoke`. Windows using them run it when the
+/// page connects, pages that need it earlier can load it after `webui.js`.
+pub const CLIENT_JS: &str = include_str!("client.js");
@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    webui::{is_live, live_windows},
+    Error, Result, WindowRef,
+};
+
+/// The windows created through rust-webui, together with data of type `T`
+/// the app attaches to them.
+///
+/// ```no_run
+/// # use rust_webui::{Window, WindowManager};
+/// struct Editor { path: String }
+///
+/// let editors = std::sync::Arc::new(WindowManager::<Editor>::new());
+/// let window = Window::new();
+/// editors.attach(&window, Editor { path: "notes.md".into() });
+///
+/// let lookup = editors.clone();
+/// window.bind("path", move |event| {
+///     lookup.data(event.window).map(|editor| editor.path.clone())
+/// })?;
+/// # Ok::<(), rust_webui::Error>(())
+/// ```
+pub struct WindowManager<T = ()> {
+    data: RwLock<HashMap<usize, Arc<T>>>,
+}
+
+impl<T> WindowManager<T> {
+    pub fn new() -> Self {
+        Self {
+            data: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Every window that hasn't been destroyed, ordered by window number.
+    pub fn windows(&self) -> Vec<WindowRef> {
+        live_windows()
+    }
+
+    /// The window with this number, if it was created through rust-webui
+    /// and hasn't been destroyed.
+    pub fn get(&self, handle: usize) -> Option<WindowRef> {
+        is_live(handle).then(|| WindowRef::from_handle(handle))
+    }
+
+    /// The windows that are currently shown.
+    pub fn shown(&self) -> Vec<WindowRef> {
+        let mut windows = live_windows();
+        windows.retain(WindowRef::is_shown);
+        windows
+    }
+
+    pub fn is_shown(&self, handle: usize) -> bool {
+        self.get(handle).is_some_and(|window| window.is_shown())
+    }
+
+    /// Run `js` in every shown window.
+    pub fn broadcast_run(&self, js: &str) -> Result<()> {
+        for window in live_windows() {
+            match window.run(js) {
+                Ok(()) | Err(Error::WindowNotShown) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Send an event to every shown window, see [`broadcast`](crate::broadcast).
+    #[cfg(feature = "serde")]
+    pub fn broadcast_emit<P: serde::Serialize + ?Sized>(
+        &self,
+        event: &str,
+        payload: &P,
+    ) -> Result<()> {
+        crate::broadcast(event, payload)
+    }
+
+    /// Attach data to a window, replacing what was attached before.
+    pub fn attach(&self, window: &WindowRef, data: T) -> Option<Arc<T>> {
+        let mut map = self.data.write().unwrap();
+        map.retain(|&handle, _| is_live(handle));
+        map.insert(window.handle(), Arc::new(data))
+    }
+
+    /// The data attached to a window, e.g. to `event.window` in a handler.
+    pub fn data(&self, window: WindowRef) -> Option<Arc<T>> {
+        if !is_live(window.handle()) {
+            return None;
+        }
+        self.data.read().unwrap().get(&window.handle()).cloned()
+    }
+
+    pub fn detach(&self, window: WindowRef) -> Option<Arc<T>> {
+        self.data.write().unwrap().remove(&window.handle())
+    }
+}
+
+impl<T> Default for WindowManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for WindowManager<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowManager")
+            .field("windows", &self.windows())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockBackend, Window};
+
+    #[test]
+    fn track_windows() {
+        let mock = MockBackend::install();
+        let manager = WindowManager::<()>::new();
+        let first = Window::new();
+        let second = Window::new();
+        assert_eq!(manager.windows(), [*first, *second]);
+        assert_eq!(manager.get(second.handle()), Some(*second));
+        assert_eq!(manager.get(second.handle() + 1), None);
+
+        second.show("<html></html>").unwrap();
+        assert_eq!(manager.shown(), [*second]);
+        assert!(manager.is_shown(second.handle()));
+        assert!(!manager.is_shown(first.handle()));
+
+        manager.broadcast_run("update()").unwrap();
+        assert!(mock.runs(&first).is_empty());
+        assert_eq!(mock.runs(&second), ["update()"]);
+    }
+
+    #[test]
+    fn dropped_windows_are_forgotten() {
+        let _mock = MockBackend::install();
+        let manager = WindowManager::new();
+        let first = Window::new();
+        let second = Window::new();
+        let handle = second.handle();
+        manager.attach(&first, "first");
+        manager.attach(&second, "second");
+        assert_eq!(manager.data(*second).as_deref(), Some(&"second"));
+
+        drop(second);
+        assert_eq!(manager.windows(), [*first]);
+        assert_eq!(manager.get(handle), None);
+        assert_eq!(manager.data(WindowRef::from_handle(handle)), None);
+
+        // Attaching prunes the data of destroyed windows.
+        let third = Window::new();
+        manager.attach(&third, "third");
+        assert_eq!(manager.data.read().unwrap().len(), 2);
+        assert_eq!(manager.detach(*first).as_deref(), Some(&"first"));
+        assert_eq!(manager.data(*first), None);
+    }
+}
@@ -0,0 +1,771 @@
+//! An in-memory [`Backend`] for testing bindings without a browser.
+//!
+//! ```
+//! # use rust_webui::{mock::MockBackend, Window};
+//! let mock = MockBackend::install();
+//! let window = Window::new();
+//! window.bind("add", |event| event.get_int_at(0) + event.get_int_at(1))?;
+//! assert_eq!(mock.call(&window, "add", &["1", "2"]).as_deref(), Some("3"));
+//! # Ok::<(), rust_webui::Error>(())
+//! ```
+
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    backend::set_backend, file_handler, webui::event_handler, Backend, Browser, EventType,
+    Response, Runtime, WindowRef,
+};
+
+type ScriptHandler = Arc<dyn Fn(usize, &str) -> Result<String, String> + Send + Sync>;
+
+#[derive(Default)]
+struct MockWindow {
+    shown: bool,
+    content: Option<String>,
+    settings: MockSettings,
+    url: Option<String>,
+    bindings: HashMap<String, usize>,
+    runs: Vec<String>,
+    raw: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Default)]
+struct MockEvent {
+    /// NUL terminated, like WebUI hands them out.
+    args: Vec<Vec<u8>>,
+    response: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    next_window: usize,
+    next_bind_id: usize,
+    next_event: usize,
+    windows: HashMap<usize, MockWindow>,
+    events: HashMap<usize, MockEvent>,
+    script_handler: Option<ScriptHandler>,
+    browsers: Option<Vec<Browser>>,
+    globals: MockGlobals,
+}
+
+/// The settings of a mock window, see [`MockBackend::settings`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockSettings {
+    pub size: Option<(u32, u32)>,
+    pub hide: bool,
+    pub kiosk: bool,
+    pub root_folder: Option<String>,
+    pub port: Option<usize>,
+    pub file_handler: bool,
+    pub position: Option<(u32, u32)>,
+    pub icon: Option<(String, String)>,
+    pub runtime: Option<Runtime>,
+    pub public: bool,
+    /// The name and path of the profile, empty for the default one.
+    pub profile: Option<(String, String)>,
+    /// Whether the browser profile was deleted.
+    pub profile_deleted: bool,
+}
+
+/// Settings of the whole app, see [`MockBackend::globals`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockGlobals {
+    pub timeout: Option<usize>,
+    pub default_root_folder: Option<String>,
+    pub tls_certificate: Option<(String, String)>,
+}
+
+/// Records what rust-webui asks WebUI to do, and fires events at bound
+/// closures.
+#[derive(Default)]
+pub struct MockBackend {
+    state: Mutex<MockState>,
+}
+
+static INSTALLED: Lazy<Arc<MockBackend>> = Lazy::new(|| {
+    let mock = Arc::new(MockBackend::default());
+    set_backend(mock.clone());
+    mock
+});
+
+/// Held while a test uses the mock, so tests don't see each other's windows
+/// and settings.
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// The installed mock, locked until dropped. Derefs to [`MockBackend`].
+pub struct MockGuard {
+    mock: Arc<MockBackend>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Deref for MockGuard {
+    type Target = MockBackend;
+
+    fn deref(&self) -> &MockBackend {
+        &self.mock
+    }
+}
+
+impl MockBackend {
+    /// Make the mock the backend of this process. Tests run in parallel, so
+    /// this waits until no other test holds a guard, then resets the script
+    /// handler and the available browsers. Each test should still use its
+    /// own windows, windows of earlier tests may be alive.
+    pub fn install() -> MockGuard {
+        let lock = TEST_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let mock = INSTALLED.clone();
+        {
+            let mut state = mock.state();
+            state.script_handler = None;
+            state.browsers = None;
+            state.globals = MockGlobals::default();
+        }
+        MockGuard { mock, _lock: lock }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Call a bound function like `webui.call(element, ...args)` and return
+    /// the response.
+    pub fn call(&self, window: &WindowRef, element: &str, args: &[&str]) -> Option<String> {
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        self.fire(window, EventType::Callback, element, &args)
+            .map(|response| String::from_utf8_lossy(&response).into_owned())
+    }
+
+    /// Fire an event at the closure bound to `element`, or to `""` if the
+    /// element isn't bound. Returns `None` if nothing is bound or the closure
+    /// didn't respond.
+    pub fn fire(
+        &self,
+        window: &WindowRef,
+        event_type: EventType,
+        element: &str,
+        args: &[&[u8]],
+    ) -> Option<Vec<u8>> {
+        let (bind_id, event_number) = {
+            let mut state = self.state();
+            let bindings = &state.windows.get(&window.handle())?.bindings;
+            let bind_id = *bindings.get(element).or_else(|| bindings.get(""))?;
+            state.next_event += 1;
+            let event_number = state.next_event;
+            let args = args
+                .iter()
+                .map(|arg| arg.iter().copied().chain([0]).collect())
+                .collect();
+            state.events.insert(
+                event_number,
+                MockEvent {
+                    args,
+                    response: None,
+                },
+            );
+            (bind_id, event_number)
+        };
+
+        let element = CString::new(element).expect("element contains nul");
+        // The state is unlocked, so the closure can call into the backend.
+        unsafe {
+            event_handler(
+                window.handle(),
+                event_type as usize,
+                element.as_ptr() as *mut _,
+                event_number,
+                bind_id,
+            )
+        };
+        self.state().events.remove(&event_number)?.response
+    }
+
+    /// Answer `WindowRef::script` calls, `Err` is reported as an exception.
+    pub fn set_script_handler(
+        &self,
+        handler: impl Fn(usize, &str) -> Result<String, String> + Send + Sync + 'static,
+    ) {
+        self.state().script_handler = Some(Arc::new(handler));
+    }
+
+    /// Make `show_browser` fail for browsers that are not in `browsers`,
+    /// `None` makes every browser available again.
+    pub fn set_browsers(&self, browsers: Option<&[Browser]>) {
+        self.state().browsers = browsers.map(<[Browser]>::to_vec);
+    }
+
+    /// Scripts passed to `WindowRef::run`, oldest first.
+    pub fn runs(&self, window: &WindowRef) -> Vec<String> {
+        self.window(window, |window| window.runs.clone())
+    }
+
+    /// Messages passed to `WindowRef::send_raw`, oldest first.
+    pub fn raw_messages(&self, window: &WindowRef) -> Vec<(String, Vec<u8>)> {
+        self.window(window, |window| window.raw.clone())
+    }
+
+    /// The content the window was last shown with.
+    pub fn content(&self, window: &WindowRef) -> Option<String> {
+        self.window(window, |window| window.content.clone())
+    }
+
+    pub fn globals(&self) -> MockGlobals {
+        self.state().globals.clone()
+    }
+
+    pub fn settings(&self, window: &WindowRef) -> MockSettings {
+        self.window(window, |window| window.settings.clone())
+    }
+
+    /// Request `path` from the window's file handler like the browser would.
+    /// Returns `None` if no handler is set or it doesn't serve `path`.
+    pub fn request(&self, window: &WindowRef, path: &str) -> Option<Response> {
+        if !self.settings(window).file_handler {
+            return None;
+        }
+        file_handler::serve(window.handle(), path)
+    }
+
+    fn window<T: Default>(&self, window: &WindowRef, f: impl FnOnce(&MockWindow) -> T) -> T {
+        self.state()
+            .windows
+            .get(&window.handle())
+            .map(f)
+            .unwrap_or_default()
+    }
+
+    fn settings_mut(&self, window: usize, f: impl FnOnce(&mut MockSettings)) {
+        f(&mut self.state().windows.entry(window).or_default().settings)
+    }
+
+    fn with_event(&self, event_number: usize, f: impl FnOnce(&mut MockEvent)) {
+        if let Some(event) = self.state().events.get_mut(&event_number) {
+            f(event)
+        }
+    }
+
+    fn arg(&self, event_number: usize, index: usize) -> Option<String> {
+        let state = self.state();
+        let arg = state.events.get(&event_number)?.args.get(index)?;
+        Some(String::from_utf8_lossy(&arg[..arg.len() - 1]).into_owned())
+    }
+}
+
+impl Backend for MockBackend {
+    fn set_timeout(&self, seconds: usize) {
+        self.state().globals.timeout = Some(seconds);
+    }
+
+    fn set_default_root_folder(&self, path: &CStr) -> bool {
+        let path = path.to_string_lossy().into_owned();
+        if !std::path::Path::new(&path).is_dir() {
+            return false;
+        }
+        self.state().globals.default_root_folder = Some(path);
+        true
+    }
+
+    #[cfg(feature = "tls")]
+    fn set_tls_certificate(&self, certificate_pem: &CStr, private_key_pem: &CStr) -> bool {
+        self.state().globals.tls_certificate = Some((
+            certificate_pem.to_string_lossy().into_owned(),
+            private_key_pem.to_string_lossy().into_owned(),
+        ));
+        true
+    }
+
+    fn is_app_running(&self) -> bool {
+        self.state().windows.values().any(|window| window.shown)
+    }
+
+    fn get_new_window_id(&self) -> usize {
+        let state = self.state();
+        let mut window = state.next_window + 1;
+        while state.windows.contains_key(&window) {
+            window += 1;
+        }
+        window
+    }
+
+    fn new_window(&self) -> usize {
+        let window = self.get_new_window_id();
+        let mut state = self.state();
+        state.next_window = window;
+        state.windows.insert(window, MockWindow::default());
+        window
+    }
+
+    fn new_window_id(&self, window: usize) {
+        self.state().windows.entry(window).or_default();
+    }
+
+    fn destroy(&self, window: usize) {
+        self.state().windows.remove(&window);
+    }
+
+    fn get_unique_window_id(&self, window: usize) -> usize {
+        window
+    }
+
+    fn set_size(&self, window: usize, width: u32, height: u32) {
+        self.settings_mut(window, |settings| settings.size = Some((width, height)));
+    }
+
+    fn set_position(&self, window: usize, x: u32, y: u32) {
+        self.settings_mut(window, |settings| settings.position = Some((x, y)));
+    }
+
+    fn set_icon(&self, window: usize, icon: &CStr, icon_type: &CStr) {
+        let icon = (
+            icon.to_string_lossy().into_owned(),
+            icon_type.to_string_lossy().into_owned(),
+        );
+        self.settings_mut(window, |settings| settings.icon = Some(icon));
+    }
+
+    fn set_runtime(&self, window: usize, runtime: Runtime) {
+        self.settings_mut(window, |settings| settings.runtime = Some(runtime));
+    }
+
+    fn set_public(&self, window: usize, public: bool) {
+        self.settings_mut(window, |settings| settings.public = public);
+    }
+
+    fn set_hide(&self, window: usize, hide: bool) {
+        self.settings_mut(window, |settings| settings.hide = hide);
+    }
+
+    fn set_kiosk(&self, window: usize, kiosk: bool) {
+        self.settings_mut(window, |settings| settings.kiosk = kiosk);
+    }
+
+    fn set_root_folder(&self, window: usize, path: &CStr) -> bool {
+        let path = path.to_string_lossy().into_owned();
+        if !std::path::Path::new(&path).is_dir() {
+            return false;
+        }
+        self.settings_mut(window, |settings| settings.root_folder = Some(path));
+        true
+    }
+
+    fn set_port(&self, window: usize, port: usize) -> bool {
+        let mut state = self.state();
+        let in_use = state
+            .windows
+            .iter()
+            .any(|(&other, w)| other != window && w.settings.port == Some(port));
+        if in_use {
+            return false;
+        }
+        state.windows.entry(window).or_default().settings.port = Some(port);
+        true
+    }
+
+    fn set_file_handler(&self, window: usize) {
+        self.settings_mut(window, |settings| settings.file_handler = true);
+    }
+
+    fn set_profile(&self, window: usize, name: &CStr, path: &CStr) {
+        let profile = (
+            name.to_string_lossy().into_owned(),
+            path.to_string_lossy().into_owned(),
+        );
+        self.settings_mut(window, |settings| settings.profile = Some(profile));
+    }
+
+    fn delete_profile(&self, window: usize) {
+        self.settings_mut(window, |settings| settings.profile_deleted = true);
+    }
+
+    fn delete_all_profiles(&self) {
+        for window in self.state().windows.values_mut() {
+            window.settings.profile_deleted = true;
+        }
+    }
+
+    fn show(&self, window: usize, content: &CStr) -> bool {
+        let mut state = self.state();
+        let Some(window) = state.windows.get_mut(&window) else {
+            return false;
+        };
+        window.shown = true;
+        window.content = Some(content.to_string_lossy().into_owned());
+        true
+    }
+
+    fn show_browser(&self, window: usize, content: &CStr, browser: Browser) -> bool {
+        let available = match &self.state().browsers {
+            Some(browsers) => browsers.contains(&browser),
+            None => true,
+        };
+        available && self.show(window, content)
+    }
+
+    fn is_shown(&self, window: usize) -> bool {
+        self.state()
+            .windows
+            .get(&window)
+            .is_some_and(|window| window.shown)
+    }
+
+    fn close(&self, window: usize) {
+        if let Some(window) = self.state().windows.get_mut(&window) {
+            window.shown = false;
+        }
+    }
+
+    fn navigate(&self, window: usize, url: &CStr) {
+        if let Some(window) = self.state().windows.get_mut(&window) {
+            window.url = Some(url.to_string_lossy().into_owned());
+        }
+    }
+
+    /// The last url navigated to, or a local url on the window's port.
+    fn get_url(&self, window: usize) -> Option<String> {
+        let state = self.state();
+        let mock = state.windows.get(&window).filter(|window| window.shown)?;
+        let port = mock.settings.port.unwrap_or(8000 + window);
+        Some(
+            mock.url
+                .clone()
+                .unwrap_or_else(|| format!("http://localhost:{}/", port)),
+        )
+    }
+
+    fn bind(&self, window: usize, element: &CStr) -> usize {
+        let mut state = self.state();
+        let element = element.to_string_lossy().into_owned();
+        if let Some(&bind_id) = state
+            .windows
+            .get(&window)
+            .and_then(|window| window.bindings.get(&element))
+        {
+            return bind_id;
+        }
+        state.next_bind_id += 1;
+        let bind_id = state.next_bind_id;
+        state
+            .windows
+            .entry(window)
+            .or_default()
+            .bindings
+            .insert(element, bind_id);
+        bind_id
+    }
+
+    fn run(&self, window: usize, script: &CStr) {
+        if let Some(window) = self.state().windows.get_mut(&window) {
+            window.runs.push(script.to_string_lossy().into_owned());
+        }
+    }
+
+    fn send_raw(&self, window: usize, function: &CStr, data: &[u8]) {
+        if let Some(window) = self.state().windows.get_mut(&window) {
+            let function = function.to_string_lossy().into_owned();
+            window.raw.push((function, data.to_vec()));
+        }
+    }
+
+    fn script(&self, window: usize, script: &CStr, timeout: usize, buffer: &mut [u8]) -> bool {
+        let handler = self.state().script_handler.clone();
+        let Some(handler) = handler else {
+            // Nothing answers, wait for the timeout like WebUI. Without one
+            // WebUI would wait forever, give up right away instead.
+            thread::sleep(Duration::from_secs(timeout as u64));
+            buffer.fill(0);
+            return false;
+        };
+        let (ok, response) = match handler(window, &script.to_string_lossy()) {
+            Ok(response) => (true, response),
+            Err(message) => (false, message),
+        };
+        let len = response.len().min(buffer.len().saturating_sub(1));
+        buffer[..len].copy_from_slice(&response.as_bytes()[..len]);
+        buffer[len..].fill(0);
+        ok
+    }
+
+    fn arg_at(&self, _window: usize, event_number: usize, index: usize) -> (*const u8, usize) {
+        let state = self.state();
+        match state
+            .events
+            .get(&event_number)
+            .and_then(|event| event.args.get(index))
+        {
+            // The buffer lives until `fire` removes the event.
+            Some(arg) => (arg.as_ptr(), arg.len() - 1),
+            None => (std::ptr::null(), 0),
+        }
+    }
+
+    fn int_at(&self, _window: usize, event_number: usize, index: usize) -> i64 {
+        self.arg(event_number, index)
+            .and_then(|arg| arg.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn bool_at(&self, _window: usize, event_number: usize, index: usize) -> bool {
+        self.arg(event_number, index)
+            .is_some_and(|arg| !matches!(arg.as_str(), "" | "false" | "0"))
+    }
+
+    fn set_response(&self, _window: usize, event_number: usize, response: &CStr) {
+        self.with_event(event_number, |event| {
+            event.response = Some(response.to_bytes().to_vec())
+        });
+    }
+
+    fn return_int(&self, _window: usize, event_number: usize, n: i64) {
+        self.with_event(event_number, |event| {
+            event.response = Some(n.to_string().into_bytes())
+        });
+    }
+
+    fn return_bool(&self, _window: usize, event_number: usize, b: bool) {
+        self.with_event(event_number, |event| {
+            event.response = Some(b.to_string().into_bytes())
+        });
+    }
+
+    fn wait(&self) {}
+
+    fn exit(&self) {
+        for window in self.state().windows.values_mut() {
+            window.shown = false;
+        }
+    }
+
+    fn clean(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, ScriptError, Window};
+
+    #[test]
+    fn call_bound_closure() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window
+            .bind("add", |event| event.get_int_at(0) + event.get_int_at(1))
+            .unwrap();
+        window.bind("nothing", |_| {}).unwrap();
+
+        assert_eq!(mock.call(&window, "add", &["1", "2"]).as_deref(), Some("3"));
+        assert_eq!(mock.call(&window, "nothing", &[]), None);
+        assert_eq!(mock.call(&window, "unbound", &[]), None);
+    }
+
+    #[test]
+    fn unbind_closure() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        let binding = window.bind("answer", |_| 42).unwrap();
+        let other = window.bind("other", |_| 1).unwrap();
+        assert_eq!(binding.window(), window.handle());
+        assert_ne!(binding.bind_id(), other.bind_id());
+
+        assert!(binding.unbind());
+        assert_eq!(mock.call(&window, "answer", &[]), None);
+        assert_eq!(mock.call(&window, "other", &[]).as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn stale_binding_keeps_new_closure() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        let old = window.bind("answer", |_| 1).unwrap();
+        let new = window.bind("answer", |_| 2).unwrap();
+        assert_eq!(old.bind_id(), new.bind_id());
+
+        assert!(!old.unbind());
+        assert_eq!(mock.call(&window, "answer", &[]).as_deref(), Some("2"));
+        assert!(new.unbind());
+        assert_eq!(mock.call(&window, "answer", &[]), None);
+
+        let again = window.bind("answer", |_| 3).unwrap();
+        window.unbind_all();
+        assert!(!again.unbind());
+    }
+
+    #[test]
+    fn read_arguments() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window
+            .bind("args", |event| {
+                format!(
+                    "{} {:?} {:?} {:?} {:?}",
+                    event.arg_count(),
+                    event.get_str_at(0),
+                    event.arg::<u32>(1),
+                    event.arg::<Option<bool>>(2),
+                    event.get_bytes_at(3),
+                )
+            })
+            .unwrap();
+
+        let response = mock.fire(
+            &window,
+            EventType::Callback,
+            "args",
+            &[b"hi", b"7", b"", b"\x01"],
+        );
+        assert_eq!(
+            String::from_utf8(response.unwrap()).unwrap(),
+            r#"4 Ok("hi") Ok(7) Ok(None) [1]"#
+        );
+        let response = mock.call(&window, "args", &["", "x"]).unwrap();
+        assert!(response.starts_with("2 "), "{}", response);
+        assert!(response.contains("Invalid"), "{}", response);
+    }
+
+    #[test]
+    fn panics_respond_with_error() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window.bind("boom", |_| -> () { panic!("boom") }).unwrap();
+
+        assert_eq!(
+            mock.call(&window, "boom", &[]).as_deref(),
+            Some(r#"{"t":"Err","c":{"kind":"panic","message":"boom"}}"#)
+        );
+    }
+
+    #[test]
+    fn run_and_script() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        assert!(matches!(window.run("1"), Err(Error::WindowNotShown)));
+
+        window.show("<html></html>").unwrap();
+        window.run("console.log(1)").unwrap();
+        assert_eq!(mock.runs(&window), ["console.log(1)"]);
+
+        let handle = window.handle();
+        mock.set_script_handler(move |window, script| match script {
+            _ if window != handle => Err("other window".to_string()),
+            "return 1;" => Ok("1".to_string()),
+            _ => Err("unknown".to_string()),
+        });
+        assert_eq!(window.script("return 1;", None).unwrap(), "1");
+        assert!(matches!(
+            window.script("throw 1;", None),
+            Err(ScriptError::Exception(message)) if message == "unknown"
+        ));
+    }
+
+    #[test]
+    fn script_timeout_or_empty_exception() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window.show("<html></html>").unwrap();
+
+        let timeout = Some(Duration::from_millis(1));
+        assert!(matches!(
+            window.script("return 1;", timeout),
+            Err(ScriptError::Timeout)
+        ));
+
+        mock.set_script_handler(|_, _| Err(String::new()));
+        assert!(matches!(
+            window.script("throw '';", timeout),
+            Err(ScriptError::Exception(message)) if message.is_empty()
+        ));
+        assert!(matches!(
+            window.script("throw '';", None),
+            Err(ScriptError::Exception(message)) if message.is_empty()
+        ));
+    }
+
+    #[test]
+    fn window_settings() {
+        let mock = MockBackend::install();
+        let mut window = Window::new();
+        let mut other = Window::new();
+        window.set_size(800, 600);
+        window.set_kiosk(true);
+        window.set_port(48123).unwrap();
+        assert!(matches!(
+            other.set_port(48123),
+            Err(Error::PortInUse(48123))
+        ));
+        assert!(window.set_root_folder("/no/such/folder").is_err());
+        other.set_root_folder(std::env::temp_dir()).unwrap();
+
+        window.set_position(10, 20);
+        window.set_icon("<svg/>", "image/svg+xml").unwrap();
+        window.set_runtime(Runtime::Deno);
+        window.set_public(true);
+
+        let settings = mock.settings(&window);
+        assert_eq!(settings.size, Some((800, 600)));
+        assert_eq!(settings.position, Some((10, 20)));
+        assert_eq!(
+            settings.icon,
+            Some(("<svg/>".to_string(), "image/svg+xml".to_string()))
+        );
+        assert_eq!(settings.runtime, Some(Runtime::Deno));
+        assert!(settings.public);
+        assert!(settings.kiosk && !settings.hide);
+        assert_eq!(settings.root_folder, None);
+        assert!(mock.settings(&other).root_folder.is_some());
+
+        assert_eq!(window.get_url(), None);
+        assert!(matches!(
+            window.navigate("http://example.com/"),
+            Err(Error::WindowNotShown)
+        ));
+        window.show("<html></html>").unwrap();
+        assert_eq!(window.get_url().as_deref(), Some("http://localhost:48123/"));
+        window.navigate("http://example.com/").unwrap();
+        assert_eq!(window.get_url().as_deref(), Some("http://example.com/"));
+    }
+
+    #[test]
+    fn global_settings() {
+        let mock = MockBackend::install();
+        crate::set_timeout(30);
+        assert!(crate::set_default_root_folder("/no/such/folder").is_err());
+        crate::set_default_root_folder(std::env::temp_dir()).unwrap();
+        let globals = mock.globals();
+        assert_eq!(globals.timeout, Some(30));
+        assert!(globals.default_root_folder.is_some());
+
+        let id = crate::get_new_window_id();
+        assert_eq!(crate::get_new_window_id(), id);
+        let window = Window::with_number(id);
+        assert_ne!(crate::get_new_window_id(), id);
+
+        window.show("<html></html>").unwrap();
+        assert!(crate::is_app_running());
+    }
+
+    #[test]
+    fn destroy_drops_closures() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        let handle = WindowRef::from_handle(window.handle());
+        let owned = Arc::new(());
+        let captured = owned.clone();
+        window
+            .bind("f", move |_| {
+                let _ = &captured;
+                1
+            })
+            .unwrap();
+        assert_eq!(mock.call(&handle, "f", &[]).as_deref(), Some("1"));
+
+        window.destroy();
+        assert_eq!(mock.call(&handle, "f", &[]), None);
+        assert_eq!(Arc::strong_count(&owned), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, fs, path::PathBuf, sync::RwLock};
+
+use crate::{backend::backend, Error, Result, WindowRef};
+
+/// Temporary profile folders by window, deleted with the window.
+static EPHEMERAL: Lazy<RwLock<HashMap<usize, PathBuf>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// The browser profile a window is shown with, so cookies and local storage
+/// can be kept apart from the user's browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// The user's default browser profile.
+    #[default]
+    Default,
+    /// A persistent profile stored at `path`.
+    Named { name: String, path: PathBuf },
+    /// A fresh profile in a temporary folder, deleted when the window is
+    /// destroyed. Windows given up with [`Window::leak`](crate::Window::leak)
+    /// keep their folder until [`clean`](crate::clean).
+    Ephemeral,
+}
+
+impl Profile {
+    pub fn named(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Profile::Named {
+            name: name.into(),
+            path: path.into(),
+        }
+    }
+}
+
+impl WindowRef {
+    /// Show the window with `profile`. Must be called before the window is
+    /// shown.
+    pub fn use_profile(&mut self, profile: Profile) -> Result<()> {
+        match profile {
+            Profile::Default => self.set_profile("", "")?,
+            Profile::Named { name, path } => self.set_profile(&name, path)?,
+            Profile::Ephemeral => {
+                // A new folder with a random name, only accessible by the
+                // current user; an existing folder is never reused.
+                let mut builder = tempfile::Builder::new();
+                builder.prefix("rust-webui-profile-");
+                #[cfg(unix)]
+                builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+                let path = builder.tempdir().map_err(Error::Io)?.keep();
+                if let Err(err) = self.set_profile("rust-webui", &path) {
+                    remove_dir(path);
+                    return Err(err);
+                }
+                let old = EPHEMERAL.write().unwrap().insert(self.handle(), path);
+                if let Some(old) = old {
+                    remove_dir(old);
+                }
+                return Ok(());
+            }
+        }
+        remove_ephemeral(self.handle());
+        Ok(())
+    }
+
+    /// Delete the local profile folder of the window's browser. This can
+    /// break other windows using the same browser.
+    pub fn delete_profile(&self) {
+        backend().delete_profile(self.handle())
+    }
+}
+
+/// Delete the local profile folders of all browsers, call it after
+/// [`wait`](crate::wait) and before [`clean`](crate::clean).
+pub fn delete_all_profiles() {
+    backend().delete_all_profiles()
+}
+
+pub(crate) fn remove_ephemeral(window: usize) {
+    let path = EPHEMERAL.write().unwrap().remove(&window);
+    if let Some(path) = path {
+        remove_dir(path);
+    }
+}
+
+pub(crate) fn remove_all_ephemeral() {
+    let paths = std::mem::take(&mut *EPHEMERAL.write().unwrap());
+    paths.into_values().for_each(remove_dir);
+}
+
+fn remove_dir(path: PathBuf) {
+    if let Err(err) = fs::remove_dir_all(&path) {
+        log::warn!("failed to delete profile {}: {}", path.display(), err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{mock::MockBackend, Window};
+
+    #[test]
+    fn ephemeral_profile_is_deleted_with_window() {
+        let mock = MockBackend::install();
+        let mut window = Window::new();
+        window.use_profile(Profile::Ephemeral).unwrap();
+        let path = EPHEMERAL.read().unwrap()[&window.handle()].clone();
+        assert!(path.is_dir());
+        let profile = mock.settings(&window).profile.unwrap();
+        assert_eq!(profile.0, "rust-webui");
+        assert_eq!(Path::new(&profile.1), path);
+
+        drop(window);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ephemeral_profiles_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _mock = MockBackend::install();
+        let mut windows = [Window::new(), Window::new()];
+        for window in &mut windows {
+            window.use_profile(Profile::Ephemeral).unwrap();
+        }
+        let ephemeral = EPHEMERAL.read().unwrap();
+        let path = &ephemeral[&windows[0].handle()];
+        assert_ne!(path, &ephemeral[&windows[1].handle()]);
+        let mode = fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn switching_profiles_deletes_the_ephemeral_one() {
+        let mock = MockBackend::install();
+        let mut window = Window::new();
+        window.use_profile(Profile::Ephemeral).unwrap();
+        let path = EPHEMERAL.read().unwrap()[&window.handle()].clone();
+
+        window.use_profile(Profile::Default).unwrap();
+        assert!(!path.exists());
+        let settings = mock.settings(&window);
+        assert_eq!(settings.profile, Some((String::new(), String::new())));
+
+        window.delete_profile();
+        assert!(mock.settings(&window).profile_deleted);
+    }
+}
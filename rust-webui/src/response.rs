@@ -0,0 +1,92 @@
+use crate::Event;
+
+/// A value a bound closure can return as the response to `webui.call`.
+///
+/// ```ignore
+/// window.bind("add", |event| event.get_int_at(0) + event.get_int_at(1))?;
+/// ```
+pub trait IntoResponse {
+    fn into_response(self, event: &mut Event);
+}
+
+/// No response, or one already set on the event.
+impl IntoResponse for () {
+    fn into_response(self, _event: &mut Event) {}
+}
+
+impl IntoResponse for bool {
+    fn into_response(self, event: &mut Event) {
+        event.return_bool(self)
+    }
+}
+
+macro_rules! impl_into_response_int {
+    ($($ty:ty),*) => {
+        $(impl IntoResponse for $ty {
+            fn into_response(self, event: &mut Event) {
+                event.return_int(self.into())
+            }
+        })*
+    };
+}
+
+impl_into_response_int!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_into_response_display {
+    ($($ty:ty),*) => {
+        $(impl IntoResponse for $ty {
+            fn into_response(self, event: &mut Event) {
+                respond(event, self.to_string().as_bytes())
+            }
+        })*
+    };
+}
+
+impl_into_response_display!(u64, usize, isize, f32, f64);
+
+impl IntoResponse for &str {
+    fn into_response(self, event: &mut Event) {
+        respond(event, self.as_bytes())
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self, event: &mut Event) {
+        respond(event, self.as_bytes())
+    }
+}
+
+impl IntoResponse for Vec<u8> {
+    fn into_response(self, event: &mut Event) {
+        respond(event, &self)
+    }
+}
+
+/// Responses containing NUL can't be passed to WebUI, they are logged and
+/// the call resolves empty.
+fn respond(event: &mut Event, response: &[u8]) {
+    if let Err(err) = event.return_bytes(response) {
+        log::error!("cannot respond to {:?}: {}", event.element, err);
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Option<T> {
+    fn into_response(self, event: &mut Event) {
+        if let Some(value) = self {
+            value.into_response(event)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock::MockBackend, Window};
+
+    #[test]
+    fn nul_in_response_is_not_a_panic() {
+        let mock = MockBackend::install();
+        let window = Window::new();
+        window.bind("nul", |_| "a\0b").unwrap();
+        assert_eq!(mock.call(&window, "nul", &[]), None);
+    }
+}
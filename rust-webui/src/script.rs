@@ -0,0 +1,102 @@
+use std::{
+    ffi::CString,
+    time::{Duration, Instant},
+};
+
+use crate::{backend::backend, Error, Result, ScriptError, WindowRef};
+
+const INITIAL_BUFFER_SIZE: usize = 4 * 1024;
+const MAX_BUFFER_SIZE: usize = 16 * 1024 * 1024;
+
+impl WindowRef {
+    /// Run JavaScript without waiting for the result.
+    pub fn run(&self, script: &str) -> Result<()> {
+        let cstring = CString::new(script)?;
+        if !self.is_shown() {
+            return Err(Error::WindowNotShown);
+        }
+        backend().run(self.handle(), &cstring);
+        Ok(())
+    }
+
+    /// Run JavaScript and return its result as a string. The script must
+    /// `return` a value, e.g. `"return document.title;"`.
+    ///
+    /// `timeout` is rounded up to whole seconds, `None` waits forever.
+    ///
+    /// WebUI copies the result into a fixed size buffer. When the result does
+    /// not fit, the buffer is grown and the script is run again, so scripts
+    /// with large results should be free of side effects.
+    pub fn script(&self, script: &str, timeout: Option<Duration>) -> Result<String, ScriptError> {
+        let cstring = CString::new(script)?;
+        let timeout = timeout.map_or(0, |timeout| {
+            let secs = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+            secs.max(1) as usize
+        });
+
+        let mut size = INITIAL_BUFFER_SIZE;
+        loop {
+            if !self.is_shown() {
+                return Err(ScriptError::WindowNotShown);
+            }
+
+            let mut buffer = vec![0u8; size];
+            let start = Instant::now();
+            // Keep the last byte as a terminator in case WebUI truncates
+            // without writing one.
+            let ok = backend().script(self.handle(), &cstring, timeout, &mut buffer[..size - 1]);
+            let len = buffer.iter().position(|&b| b == 0).unwrap_or(size - 1);
+
+            if len + 2 >= size {
+                if size >= MAX_BUFFER_SIZE {
+                    return Err(ScriptError::ResponseTooLarge);
+                }
+                size *= 2;
+                continue;
+            }
+
+            buffer.truncate(len);
+            let response = String::from_utf8(buffer).map_err(|err| err.utf8_error());
+            return match (ok, response) {
+                (true, response) => response.map_err(ScriptError::InvalidUtf8),
+                // WebUI leaves the buffer empty both when no response arrived
+                // and when the script threw an empty value, e.g. `throw ""`.
+                // Only waiting for a response takes the whole timeout.
+                (false, Ok(message)) if message.is_empty() => {
+                    if !self.is_shown() {
+                        Err(ScriptError::WindowNotShown)
+                    } else if timeout > 0 && start.elapsed() >= Duration::from_secs(timeout as u64)
+                    {
+                        Err(ScriptError::Timeout)
+                    } else {
+                        Err(ScriptError::Exception(message))
+                    }
+                }
+                (false, Ok(message)) => Err(ScriptError::Exception(message)),
+                (false, Err(err)) => Err(ScriptError::InvalidUtf8(err)),
+            };
+        }
+    }
+
+    /// Run JavaScript and deserialize its JSON-stringified result.
+    ///
+    /// ```no_run
+    /// # use rust_webui::Window;
+    /// # let window = Window::new();
+    /// let items: Vec<String> = window.script_json(
+    ///     "return [...document.querySelectorAll('li')].map(li => li.textContent);",
+    ///     None,
+    /// )?;
+    /// # Ok::<(), rust_webui::ScriptError>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn script_json<T: serde::de::DeserializeOwned>(
+        &self,
+        script: &str,
+        timeout: Option<Duration>,
+    ) -> Result<T, ScriptError> {
+        let wrapped = format!("return JSON.stringify((() => {{ {} }})());", script);
+        let response = self.script(&wrapped, timeout)?;
+        serde_json::from_str(&response).map_err(ScriptError::Json)
+    }
+}
@@ -0,0 +1,90 @@
+use once_cell::sync::Lazy;
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{Event, WindowRef};
+
+type StateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+/// Managed state by window, dropped when the window is destroyed.
+static STATES: Lazy<RwLock<HashMap<usize, StateMap>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+impl WindowRef {
+    /// Store `state` with the window, so bound closures can get it with
+    /// [`Event::state`] instead of capturing it:
+    ///
+    /// ```no_run
+    /// # use rust_webui::Window;
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// struct Counter(AtomicU32);
+    ///
+    /// let window = Window::new();
+    /// window.manage(Counter(AtomicU32::new(0)));
+    /// window.bind("increment", |event| {
+    ///     event.state::<Counter>().0.fetch_add(1, Ordering::Relaxed) + 1
+    /// })?;
+    /// # Ok::<(), rust_webui::Error>(())
+    /// ```
+    ///
+    /// There is one value per type, returns `false` and keeps the old value if
+    /// the window already has state of type `T`.
+    pub fn manage<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        let mut states = STATES.write().unwrap();
+        let map = states.entry(self.handle()).or_default();
+        if map.contains_key(&TypeId::of::<T>()) {
+            return false;
+        }
+        map.insert(TypeId::of::<T>(), Arc::new(state));
+        true
+    }
+
+    pub fn try_state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        let states = STATES.read().unwrap();
+        let state = states.get(&self.handle())?.get(&TypeId::of::<T>())?.clone();
+        state.downcast().ok()
+    }
+
+    /// The state of type `T`, see [`manage`](WindowRef::manage).
+    ///
+    /// # Panics
+    ///
+    /// If the window doesn't manage a `T`.
+    pub fn state<T: Send + Sync + 'static>(&self) -> Arc<T> {
+        self.try_state().unwrap_or_else(|| {
+            panic!(
+                "window {} doesn't manage state of type {}",
+                self.handle(),
+                type_name::<T>()
+            )
+        })
+    }
+}
+
+impl<'a> Event<'a> {
+    /// The state of type `T` of the window, see [`WindowRef::manage`].
+    ///
+    /// # Panics
+    ///
+    /// If the window doesn't manage a `T`.
+    pub fn state<T: Send + Sync + 'static>(&self) -> Arc<T> {
+        self.window.state()
+    }
+
+    pub fn try_state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.window.try_state()
+    }
+}
+
+pub(crate) fn remove_states(window: usize) {
+    // Take the map out first, so `Drop` impls of the state can lock again.
+    let states = STATES.write().unwrap().remove(&window);
+    drop(states);
+}
+
+pub(crate) fn remove_all_states() {
+    let states = std::mem::take(&mut *STATES.write().unwrap());
+    drop(states);
+}
@@ -0,0 +1,503 @@
+//! A client that talks to a window like `webui.js` does, for end-to-end
+//! tests on machines without a browser.
+//!
+//! ```no_run
+//! # use rust_webui::{test_client::TestClient, Browser, Window};
+//! let window = Window::new();
+//! window.bind("add", |event| event.get_int_at(0) + event.get_int_at(1))?;
+//! window.show_browser("<html><script src=\"webui.js\"></script></html>", Browser::NoBrowser)?;
+//!
+//! let mut client = TestClient::connect(&window)?;
+//! assert_eq!(client.call("add", &["1", "2"])?, "3");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! The protocol is the one of the WebUI 2.4 bridge. Every packet starts
+//! with a header, followed by command specific data:
+//!
+//! | offset | size | field                                  |
+//! |--------|------|----------------------------------------|
+//! | 0      | 1    | signature, `0xDD`                      |
+//! | 1      | 4    | token from `webui.js`, little endian   |
+//! | 5      | 2    | packet id, little endian               |
+//! | 7      | 1    | command                                |
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+
+use tungstenite::{protocol::WebSocket, Message as WsMessage};
+
+use crate::WindowRef;
+
+const SIGNATURE: u8 = 0xDD;
+const HEADER_SIZE: usize = 8;
+
+const CMD_JS: u8 = 254;
+const CMD_JS_QUICK: u8 = 253;
+const CMD_CLICK: u8 = 252;
+const CMD_NAVIGATION: u8 = 251;
+const CMD_CLOSE: u8 = 250;
+const CMD_CALL_FUNC: u8 = 249;
+const CMD_SEND_RAW: u8 = 248;
+const CMD_CHECK_TK: u8 = 245;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A message sent by the window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// A script from [`WindowRef::run`].
+    Run(String),
+    /// A script from [`WindowRef::script`], answer it with
+    /// [`TestClient::respond`].
+    Script { id: u16, script: String },
+    /// Data from [`WindowRef::send_raw`].
+    Raw { function: String, data: Vec<u8> },
+    /// The window navigates to this url.
+    Navigate(String),
+    /// The window was closed.
+    Close,
+    /// A command this client doesn't know.
+    Other { command: u8, data: Vec<u8> },
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The window has no url, because it isn't shown.
+    NotShown,
+    Io(io::Error),
+    WebSocket(Box<tungstenite::Error>),
+    /// The server sent something this client doesn't understand.
+    Protocol(String),
+    /// No answer arrived in time, see [`TestClient::set_timeout`].
+    Timeout,
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NotShown => f.write_str("window is not shown"),
+            ClientError::Io(err) => write!(f, "io error: {}", err),
+            ClientError::WebSocket(err) => write!(f, "websocket error: {}", err),
+            ClientError::Protocol(message) => write!(f, "protocol error: {}", message),
+            ClientError::Timeout => f.write_str("timed out"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Io(err) => Some(err),
+            ClientError::WebSocket(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(value: io::Error) -> Self {
+        ClientError::Io(value)
+    }
+}
+
+impl From<tungstenite::Error> for ClientError {
+    fn from(value: tungstenite::Error) -> Self {
+        match value {
+            tungstenite::Error::Io(err) => ClientError::from(err),
+            err => ClientError::WebSocket(Box::new(err)),
+        }
+    }
+}
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+pub struct TestClient {
+    socket: WebSocket<TcpStream>,
+    token: u32,
+    next_id: u16,
+    timeout: Duration,
+    /// Messages that arrived while waiting for a call response.
+    queue: VecDeque<Message>,
+}
+
+impl fmt::Debug for TestClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestClient")
+            .field("token", &self.token)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TestClient {
+    /// Connect to a shown window, e.g. one shown with
+    /// [`Browser::NoBrowser`](crate::Browser::NoBrowser).
+    pub fn connect(window: &WindowRef) -> Result<Self> {
+        let url = window.get_url().ok_or(ClientError::NotShown)?;
+        Self::connect_url(&url)
+    }
+
+    /// Connect to the window served at `url`, e.g. `http://localhost:8080/`.
+    pub fn connect_url(url: &str) -> Result<Self> {
+        let host = url
+            .strip_prefix("http://")
+            .ok_or_else(|| ClientError::Protocol(format!("unsupported url {}", url)))?
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let token = fetch_token(&host)?;
+        let stream = TcpStream::connect(&host)?;
+        stream.set_read_timeout(Some(DEFAULT_TIMEOUT))?;
+        let request = format!("ws://{}/_webui_ws_connect", host);
+        let (socket, _) = tungstenite::client(request, stream).map_err(|err| match err {
+            tungstenite::HandshakeError::Failure(err) => ClientError::from(err),
+            tungstenite::HandshakeError::Interrupted(_) => ClientError::Timeout,
+        })?;
+
+        let mut client = Self {
+            socket,
+            token,
+            next_id: 0,
+            timeout: DEFAULT_TIMEOUT,
+            queue: VecDeque::new(),
+        };
+        let id = client.send(CMD_CHECK_TK, &[])?;
+        let data = client.wait_for(CMD_CHECK_TK, id)?;
+        if data.first() == Some(&0) {
+            return Err(ClientError::Protocol("token rejected".to_string()));
+        }
+        Ok(client)
+    }
+
+    /// How long to wait for answers and messages, 10 seconds by default.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Call a bound function like `webui.call(function, ...args)`.
+    pub fn call(&mut self, function: &str, args: &[&str]) -> Result<String> {
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        let response = self.call_bytes(function, &args)?;
+        String::from_utf8(response).map_err(|err| ClientError::Protocol(err.to_string()))
+    }
+
+    /// Call a bound function with raw arguments, like passing `Uint8Array`s.
+    pub fn call_bytes(&mut self, function: &str, args: &[&[u8]]) -> Result<Vec<u8>> {
+        // [function, 0, "len;len;...", 0, arg, 0, arg, 0, ...]
+        let mut data = function.as_bytes().to_vec();
+        data.push(0);
+        for arg in args {
+            data.extend_from_slice(format!("{};", arg.len()).as_bytes());
+        }
+        data.push(0);
+        for arg in args {
+            data.extend_from_slice(arg);
+            data.push(0);
+        }
+        let id = self.send(CMD_CALL_FUNC, &data)?;
+        let mut response = self.wait_for(CMD_CALL_FUNC, id)?;
+        trim_nul(&mut response);
+        Ok(response)
+    }
+
+    /// Click the element with this id.
+    pub fn click(&mut self, element: &str) -> Result<()> {
+        self.send(CMD_CLICK, element.as_bytes())?;
+        Ok(())
+    }
+
+    /// Answer a [`Message::Script`], `Err` makes the script throw.
+    pub fn respond(&mut self, id: u16, result: Result<&str, &str>) -> Result<()> {
+        let (error, output) = match result {
+            Ok(output) => (0, output),
+            Err(message) => (1, message),
+        };
+        let mut data = vec![error];
+        data.extend_from_slice(output.as_bytes());
+        self.send_with_id(CMD_JS, id, &data)
+    }
+
+    /// The next message from the window.
+    pub fn recv(&mut self) -> Result<Message> {
+        if let Some(message) = self.queue.pop_front() {
+            return Ok(message);
+        }
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let packet = self.read_packet(deadline)?;
+            if let Some(message) = parse_message(packet.id, packet.command, packet.data) {
+                return Ok(message);
+            }
+        }
+    }
+
+    pub fn close(mut self) -> Result<()> {
+        self.socket.close(None)?;
+        Ok(())
+    }
+
+    fn send(&mut self, command: u8, data: &[u8]) -> Result<u16> {
+        self.next_id = self.next_id.wrapping_add(1);
+        let id = self.next_id;
+        self.send_with_id(command, id, data)?;
+        Ok(id)
+    }
+
+    fn send_with_id(&mut self, command: u8, id: u16, data: &[u8]) -> Result<()> {
+        let packet = Packet {
+            token: self.token,
+            id,
+            command,
+            data: data.to_vec(),
+        };
+        self.socket.send(WsMessage::Binary(packet.encode()))?;
+        Ok(())
+    }
+
+    /// Read until the answer to packet `id` arrives, queueing other messages.
+    fn wait_for(&mut self, command: u8, id: u16) -> Result<Vec<u8>> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let packet = self.read_packet(deadline)?;
+            if packet.command == command && packet.id == id {
+                return Ok(packet.data);
+            }
+            if let Some(message) = parse_message(packet.id, packet.command, packet.data) {
+                self.queue.push_back(message);
+            }
+        }
+    }
+
+    fn read_packet(&mut self, deadline: Instant) -> Result<Packet> {
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+                .ok_or(ClientError::Timeout)?;
+            self.socket.get_ref().set_read_timeout(Some(remaining))?;
+
+            let packet = match self.socket.read() {
+                Ok(WsMessage::Binary(packet)) => packet,
+                Ok(WsMessage::Text(text)) => text.into_bytes(),
+                Ok(WsMessage::Close(_)) => {
+                    return Ok(Packet {
+                        token: self.token,
+                        id: 0,
+                        command: CMD_CLOSE,
+                        data: Vec::new(),
+                    })
+                }
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(err))
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Err(ClientError::Timeout)
+                }
+                Err(err) => return Err(err.into()),
+            };
+            return Packet::decode(packet);
+        }
+    }
+}
+
+/// A packet of the bridge protocol, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Packet {
+    token: u32,
+    id: u16,
+    command: u8,
+    data: Vec<u8>,
+}
+
+impl Packet {
+    fn encode(&self) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(HEADER_SIZE + self.data.len());
+        packet.push(SIGNATURE);
+        packet.extend_from_slice(&self.token.to_le_bytes());
+        packet.extend_from_slice(&self.id.to_le_bytes());
+        packet.push(self.command);
+        packet.extend_from_slice(&self.data);
+        packet
+    }
+
+    fn decode(mut packet: Vec<u8>) -> Result<Self> {
+        if packet.len() < HEADER_SIZE || packet[0] != SIGNATURE {
+            return Err(ClientError::Protocol(format!(
+                "invalid packet header {:?}",
+                &packet[..packet.len().min(HEADER_SIZE)]
+            )));
+        }
+        let data = packet.split_off(HEADER_SIZE);
+        Ok(Self {
+            token: u32::from_le_bytes([packet[1], packet[2], packet[3], packet[4]]),
+            id: u16::from_le_bytes([packet[5], packet[6]]),
+            command: packet[7],
+            data,
+        })
+    }
+}
+
+fn parse_message(id: u16, command: u8, mut data: Vec<u8>) -> Option<Message> {
+    let text = |mut data: Vec<u8>| {
+        trim_nul(&mut data);
+        String::from_utf8_lossy(&data).into_owned()
+    };
+    Some(match command {
+        CMD_JS_QUICK => Message::Run(text(data)),
+        CMD_JS => Message::Script {
+            id,
+            script: text(data),
+        },
+        CMD_SEND_RAW => {
+            // [function, 0, data]
+            let split = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+            let rest = data.split_off((split + 1).min(data.len()));
+            Message::Raw {
+                function: text(data),
+                data: rest,
+            }
+        }
+        CMD_NAVIGATION => Message::Navigate(text(data)),
+        CMD_CLOSE => Message::Close,
+        // Late answers to calls that timed out.
+        CMD_CALL_FUNC | CMD_CHECK_TK => return None,
+        command => Message::Other { command, data },
+    })
+}
+
+fn trim_nul(data: &mut Vec<u8>) {
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+}
+
+/// WebUI generates `webui.js` per window with the token the server expects
+/// in every packet.
+fn fetch_token(host: &str) -> Result<u32> {
+    let mut stream = TcpStream::connect(host)?;
+    stream.set_read_timeout(Some(DEFAULT_TIMEOUT))?;
+    write!(
+        stream,
+        "GET /webui.js HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        host
+    )?;
+    let mut body = Vec::new();
+    stream.read_to_end(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+    bridge_token(&body)
+}
+
+/// The token in the bridge config at the end of `webui.js`:
+/// `new WebuiBridge({ secure: false, token: 123, port: 8080, ... })`.
+fn bridge_token(script: &str) -> Result<u32> {
+    let config = script
+        .rfind("new WebuiBridge(")
+        .map(|start| &script[start + "new WebuiBridge(".len()..])
+        .and_then(|rest| rest.trim_start().strip_prefix('{'))
+        .and_then(|rest| rest.split_once('}'))
+        .map(|(config, _)| config)
+        .ok_or_else(|| ClientError::Protocol("no WebuiBridge config in webui.js".to_string()))?;
+
+    // The token comes before the bind list, whose names may contain anything.
+    config
+        .split(',')
+        .find_map(|entry| {
+            let (key, value) = entry.split_once(':')?;
+            (key.trim() == "token").then(|| value.trim())
+        })
+        .ok_or_else(|| ClientError::Protocol("no token in the WebuiBridge config".to_string()))?
+        .parse()
+        .map_err(|_| ClientError::Protocol("invalid token in the WebuiBridge config".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_messages() {
+        assert_eq!(
+            parse_message(1, CMD_JS_QUICK, b"alert(1)\0".to_vec()),
+            Some(Message::Run("alert(1)".to_string()))
+        );
+        assert_eq!(
+            parse_message(2, CMD_SEND_RAW, b"recv\0\x01\x00\x02".to_vec()),
+            Some(Message::Raw {
+                function: "recv".to_string(),
+                data: vec![1, 0, 2],
+            })
+        );
+        assert_eq!(parse_message(3, CMD_CALL_FUNC, Vec::new()), None);
+    }
+
+    #[test]
+    fn packets_round_trip() {
+        assert_eq!(
+            [CMD_JS, CMD_JS_QUICK, CMD_CLICK, CMD_NAVIGATION, CMD_CLOSE],
+            [254, 253, 252, 251, 250]
+        );
+        assert_eq!([CMD_CALL_FUNC, CMD_SEND_RAW, CMD_CHECK_TK], [249, 248, 245]);
+
+        let packet = Packet {
+            token: 0x0403_0201,
+            id: 0x0605,
+            command: CMD_CALL_FUNC,
+            data: b"add\x001;1;\x001\x002\x00".to_vec(),
+        };
+        let encoded = packet.encode();
+        assert_eq!(encoded[..HEADER_SIZE], [0xDD, 1, 2, 3, 4, 5, 6, 249]);
+        assert_eq!(&encoded[HEADER_SIZE..], packet.data);
+        assert_eq!(Packet::decode(encoded).unwrap(), packet);
+
+        let empty = Packet {
+            data: Vec::new(),
+            ..packet
+        };
+        assert_eq!(Packet::decode(empty.encode()).unwrap(), empty);
+    }
+
+    #[test]
+    fn invalid_packets() {
+        for packet in [
+            vec![],
+            vec![0xDD, 1, 2, 3, 4, 5, 6],
+            vec![0xDE, 1, 2, 3, 4, 5, 6, 249],
+        ] {
+            assert!(matches!(
+                Packet::decode(packet),
+                Err(ClientError::Protocol(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn bridge_tokens() {
+        let script = "class WebuiBridge { token = 0; #token: 7; }\n\
+            document.addEventListener(\"DOMContentLoaded\", function() {\n\
+            globalThis.webui = new WebuiBridge({ secure: false, token: 3735928559, \
+            port: 8080, bindList: [\"token: 1\", \"add\"] });\n});";
+        assert_eq!(bridge_token(script).unwrap(), 3735928559);
+
+        for script in [
+            "let token = 42;",
+            "new WebuiBridge({ secure: false, port: 8080 })",
+            "new WebuiBridge({ token: abc })",
+        ] {
+            assert!(matches!(
+                bridge_token(script),
+                Err(ClientError::Protocol(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,672 @@
+//! TypeScript bindings for typed JSON handlers.
+//!
+//! Mark handlers with [`command`](crate::command) and collect them in an
+//! [`Api`], which binds them to windows and writes the TypeScript without
+//! needing a window, e.g. from a `cargo run --bin` step of the frontend
+//! build:
+//!
+//! ```ignore
+//! #[derive(Deserialize, TsType)]
+//! struct Add2Input { x: f64, y: f64 }
+//!
+//! #[command]
+//! fn add2(input: Add2Input) -> Result<f64, CommandError> {
+//!     Ok(input.x + input.y)
+//! }
+//!
+//! pub fn api() -> Api {
+//!     Api::new().command::<add2>()
+//! }
+//!
+//! // In the app:
+//! api().bind(&window)?;
+//! // In `src/bin/export-typescript.rs`:
+//! api().export_typescript("ui/src")?;
+//! ```
+//!
+//! This writes `webui-types.d.ts` with the argument and output types, and
+//! `webui-client.ts` with one function per command:
+//!
+//! ```ts
+//! export const add2 = (arg0: T.Add2Input): Promise<number> => invoke("add2", arg0);
+//! ```
+//!
+//! Closures bound with [`WindowRef::bind_typed`] are recorded too, see
+//! [`WindowRef::export_typescript`].
+
+use once_cell::sync::Lazy;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs,
+    path::Path,
+    sync::RwLock,
+};
+
+use crate::{
+    json::{Command, CommandResult, JsonHandler},
+    Binding, Error, Result, WindowRef,
+};
+
+pub use rust_webui_macros::TsType;
+
+/// A Rust type with a TypeScript equivalent.
+pub trait TsType {
+    /// The type as written in a signature, e.g. `number` or `Add2Input`.
+    fn ts_type() -> String;
+
+    /// Add the declarations this type needs, named types add their own.
+    fn ts_declare(_declarations: &mut Declarations) {}
+}
+
+/// TypeScript declarations by type name.
+#[derive(Debug, Default)]
+pub struct Declarations(BTreeMap<String, String>);
+
+impl Declarations {
+    /// Reserve `name` before declaring its fields, so recursive types stop.
+    /// Returns `false` if it is already declared or reserved.
+    pub fn reserve(&mut self, name: &str) -> bool {
+        if self.0.contains_key(name) {
+            return false;
+        }
+        self.0.insert(name.to_string(), String::new());
+        true
+    }
+
+    pub fn insert(&mut self, name: &str, declaration: String) {
+        self.0.insert(name.to_string(), declaration);
+    }
+}
+
+macro_rules! impl_ts_type {
+    ($ts:literal: $($ty:ty),*) => {
+        $(impl TsType for $ty {
+            fn ts_type() -> String {
+                $ts.to_string()
+            }
+        })*
+    };
+}
+
+impl_ts_type!("boolean": bool);
+impl_ts_type!("number": i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+impl_ts_type!("string": char, str, String);
+impl_ts_type!("null": ());
+impl_ts_type!("unknown": serde_json::Value);
+
+impl<T: TsType + ?Sized> TsType for &T {
+    fn ts_type() -> String {
+        T::ts_type()
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        T::ts_declare(declarations)
+    }
+}
+
+impl<T: TsType + ?Sized> TsType for Box<T> {
+    fn ts_type() -> String {
+        T::ts_type()
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        T::ts_declare(declarations)
+    }
+}
+
+impl<T: TsType> TsType for Option<T> {
+    fn ts_type() -> String {
+        format!("{} | null", T::ts_type())
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        T::ts_declare(declarations)
+    }
+}
+
+impl<T: TsType> TsType for Vec<T> {
+    fn ts_type() -> String {
+        format!("({})[]", T::ts_type())
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        T::ts_declare(declarations)
+    }
+}
+
+impl<T: TsType> TsType for [T] {
+    fn ts_type() -> String {
+        Vec::<T>::ts_type()
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        T::ts_declare(declarations)
+    }
+}
+
+impl<V: TsType, S> TsType for HashMap<String, V, S> {
+    fn ts_type() -> String {
+        format!("Record<string, {}>", V::ts_type())
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        V::ts_declare(declarations)
+    }
+}
+
+impl<V: TsType> TsType for BTreeMap<String, V> {
+    fn ts_type() -> String {
+        HashMap::<String, V>::ts_type()
+    }
+
+    fn ts_declare(declarations: &mut Declarations) {
+        V::ts_declare(declarations)
+    }
+}
+
+macro_rules! impl_ts_tuple {
+    ($($ty:ident),*) => {
+        impl<$($ty: TsType),*> TsType for ($($ty,)*) {
+            fn ts_type() -> String {
+                let types: &[String] = &[$($ty::ts_type()),*];
+                format!("[{}]", types.join(", "))
+            }
+
+            fn ts_declare(declarations: &mut Declarations) {
+                $($ty::ts_declare(declarations);)*
+            }
+        }
+    };
+}
+
+impl_ts_tuple!(A);
+impl_ts_tuple!(A, B);
+impl_ts_tuple!(A, B, C);
+impl_ts_tuple!(A, B, C, D);
+
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub args: Vec<String>,
+    pub output: String,
+}
+
+/// A [`JsonHandler`] whose arguments and output implement [`TsType`].
+pub trait TsHandler<Args>: JsonHandler<Args> {
+    fn signature(declarations: &mut Declarations) -> Signature;
+}
+
+macro_rules! impl_ts_handler {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg,)*> TsHandler<($($arg,)*)> for F
+        where
+            F: JsonHandler<($($arg,)*)> + Fn($($arg),*) -> R,
+            R: CommandResult,
+            R::Output: TsType,
+            $($arg: TsType,)*
+        {
+            fn signature(declarations: &mut Declarations) -> Signature {
+                $($arg::ts_declare(declarations);)*
+                <R::Output as TsType>::ts_declare(declarations);
+                Signature {
+                    args: vec![$($arg::ts_type()),*],
+                    output: <R::Output as TsType>::ts_type(),
+                }
+            }
+        }
+    };
+}
+
+impl_ts_handler!();
+impl_ts_handler!(A0);
+impl_ts_handler!(A0, A1);
+impl_ts_handler!(A0, A1, A2);
+impl_ts_handler!(A0, A1, A2, A3);
+impl_ts_handler!(A0, A1, A2, A3, A4);
+impl_ts_handler!(A0, A1, A2, A3, A4, A5);
+
+type Binder = fn(&WindowRef) -> Result<Binding>;
+
+/// A set of typed handlers and their TypeScript signatures.
+#[derive(Debug, Default)]
+pub struct Api {
+    declarations: Declarations,
+    signatures: BTreeMap<String, Signature>,
+    binders: Vec<Binder>,
+}
+
+impl Api {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a function marked with [`command`](crate::command).
+    pub fn command<C: Command>(mut self) -> Self
+    where
+        C::Handler: TsHandler<C::Args>,
+    {
+        self.record::<C::Args, C::Handler>(C::NAME);
+        self.binders.push(|window| window.bind_command::<C>());
+        self
+    }
+
+    fn record<Args, H: TsHandler<Args>>(&mut self, name: &str) {
+        let signature = H::signature(&mut self.declarations);
+        self.signatures.insert(name.to_string(), signature);
+    }
+
+    /// Bind every command to `window`.
+    pub fn bind(&self, window: &WindowRef) -> Result<()> {
+        for bind in &self.binders {
+            bind(window)?;
+        }
+        Ok(())
+    }
+
+    pub fn typescript(&self) -> TypeScript {
+        let mut declarations = format!("{}\n{}", HEADER, PRELUDE);
+        for declaration in self.declarations.0.values() {
+            declarations.push('\n');
+            declarations.push_str(declaration);
+            declarations.push('\n');
+        }
+
+        let mut client = format!("{}\n{}", HEADER, CLIENT_PRELUDE);
+        for (name, signature) in &self.signatures {
+            let params: Vec<String> = signature
+                .args
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| format!("arg{}: {}", i, qualify(ty, &self.declarations)))
+                .collect();
+            let args: String = (0..signature.args.len())
+                .map(|i| format!(", arg{}", i))
+                .collect();
+            let _ = write!(
+                client,
+                "\nexport const {} = ({}): Promise<{}> => invoke({}{});\n",
+                identifier(name),
+                params.join(", "),
+                qualify(&signature.output, &self.declarations),
+                serde_json::to_string(name).unwrap(),
+                args,
+            );
+        }
+
+        TypeScript {
+            declarations,
+            client,
+        }
+    }
+
+    /// Write `webui-types.d.ts` and `webui-client.ts` into `dir`. Files are
+    /// only written when their content changed, so dev servers don't reload.
+    pub fn export_typescript(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        let ts = self.typescript();
+        write_if_changed(&dir.join("webui-types.d.ts"), &ts.declarations)?;
+        write_if_changed(&dir.join("webui-client.ts"), &ts.client)?;
+        Ok(())
+    }
+}
+
+/// Handlers bound with `bind_typed`, by window.
+static APIS: Lazy<RwLock<HashMap<usize, Api>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub(crate) fn remove_api(window: usize) {
+    APIS.write().unwrap().remove(&window);
+}
+
+pub(crate) fn remove_all_apis() {
+    APIS.write().unwrap().clear();
+}
+
+/// Generated TypeScript sources.
+#[derive(Debug, Clone)]
+pub struct TypeScript {
+    /// Contents of `webui-types.d.ts`.
+    pub declarations: String,
+    /// Contents of `webui-client.ts`.
+    pub client: String,
+}
+
+const HEADER: &str = "// Generated by rust-webui, do not edit.\n";
+
+const PRELUDE: &str = r#"export type CommandError = {
+    kind: "invalid_argument" | "handler" | "internal" | "panic";
+    message: string;
+    index?: number;
+    data?: unknown;
+};
+
+export type Envelope<T> = { t: "Ok"; c: T } | { t: "Err"; c: CommandError };
+"#;
+
+const CLIENT_PRELUDE: &str = r#"import type * as T from "./webui-types";
+
+declare const webui: { call(fn: string, ...args: string[]): Promise<string> };
+
+export class WebuiError extends Error {
+    constructor(public readonly detail: T.CommandError) {
+        super(detail.message);
+    }
+}
+
+async function invoke<R>(name: string, ...args: unknown[]): Promise<R> {
+    while (typeof webui === "undefined") {
+        await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+    const response = await webui.call(name, ...args.map((arg) => JSON.stringify(arg)));
+    const result: T.Envelope<R> = JSON.parse(response);
+    if (result.t === "Ok") {
+        return result.c;
+    }
+    throw new WebuiError(result.c);
+}
+"#;
+
+impl WindowRef {
+    /// Same as [`bind_json`](WindowRef::bind_json), and records the signature
+    /// for [`typescript`](WindowRef::typescript).
+    pub fn bind_typed<Args, H>(&self, element: &str, handler: H) -> Result<Binding>
+    where
+        H: TsHandler<Args>,
+    {
+        APIS.write()
+            .unwrap()
+            .entry(self.handle())
+            .or_default()
+            .record::<Args, H>(element);
+        self.bind_json(element, handler)
+    }
+
+    /// Same as [`bind_command`](WindowRef::bind_command), and records the
+    /// signature like [`bind_typed`](WindowRef::bind_typed).
+    pub fn bind_typed_command<C: Command>(&self) -> Result<Binding>
+    where
+        C::Handler: TsHandler<C::Args>,
+    {
+        self.bind_typed(C::NAME, C::handler())
+    }
+
+    /// Generate TypeScript for the handlers bound with `bind_typed`.
+    pub fn typescript(&self) -> TypeScript {
+        let apis = APIS.read().unwrap();
+        match apis.get(&self.handle()) {
+            Some(api) => api.typescript(),
+            None => Api::default().typescript(),
+        }
+    }
+
+    /// Write the TypeScript for the handlers bound with `bind_typed`, see
+    /// [`Api::export_typescript`].
+    ///
+    /// This only runs with the app, prefer exporting an [`Api`] from a build
+    /// step so the frontend types can't go stale.
+    pub fn export_typescript(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let apis = APIS.read().unwrap();
+        match apis.get(&self.handle()) {
+            Some(api) => api.export_typescript(dir),
+            None => Api::default().export_typescript(dir),
+        }
+    }
+}
+
+fn write_if_changed(path: &Path, contents: &str) -> Result<()> {
+    if fs::read_to_string(path).is_ok_and(|old| old == contents) {
+        return Ok(());
+    }
+    fs::write(path, contents).map_err(Error::Io)
+}
+
+/// Prefix declared type names with the `T.` namespace of the client import.
+/// Prefix the types declared in `webui-types.d.ts` with the `T.` namespace
+/// the client imports them as. `Record<..>` is TypeScript's, declared types
+/// are never generic.
+fn qualify(ty: &str, declarations: &Declarations) -> String {
+    let mut out = String::with_capacity(ty.len());
+    let mut word = String::new();
+    let mut chars = ty.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            if chars
+                .peek()
+                .is_some_and(|&c| c.is_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            if declarations.0.contains_key(&word) && chars.peek() != Some(&'<') {
+                out.push_str("T.");
+            }
+            out.push_str(&word);
+            word.clear();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Reserved words of strict mode JavaScript and TypeScript, which can't name
+/// a `const`.
+const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+fn identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty()
+        || ident.starts_with(|c: char| c.is_ascii_digit())
+        || RESERVED.contains(&ident.as_str())
+    {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualify_declared_types() {
+        let mut declarations = Declarations::default();
+        for name in ["Item", "Record"] {
+            declarations.insert(name, String::new());
+        }
+        assert_eq!(qualify("number", &declarations), "number");
+        assert_eq!(qualify("Items", &declarations), "Items");
+        assert_eq!(
+            qualify("(Item | null)[]", &declarations),
+            "(T.Item | null)[]"
+        );
+        assert_eq!(
+            qualify("Record<string, Record>", &declarations),
+            "Record<string, T.Record>"
+        );
+    }
+
+    #[test]
+    fn reserved_identifiers() {
+        assert_eq!(identifier("delete"), "_delete");
+        assert_eq!(identifier("await"), "_await");
+        assert_eq!(identifier("get-user"), "get_user");
+        assert_eq!(identifier("2fa"), "_2fa");
+        assert_eq!(identifier(""), "_");
+        assert_eq!(identifier("deleteUser"), "deleteUser");
+    }
+
+    fn declaration<T: TsType>() -> String {
+        let mut declarations = Declarations::default();
+        T::ts_declare(&mut declarations);
+        declarations.0.remove(&T::ts_type()).unwrap()
+    }
+
+    #[allow(dead_code)]
+    #[derive(serde::Serialize, serde::Deserialize, TsType)]
+    #[serde(rename_all = "camelCase")]
+    struct User {
+        user_id: u64,
+        #[serde(rename = "display \"name\"")]
+        name: String,
+        #[serde(skip)]
+        password: String,
+        friends: Vec<User>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(serde::Deserialize, TsType)]
+    #[serde(rename = "UserId")]
+    struct Id(u64);
+
+    #[allow(dead_code)]
+    #[derive(serde::Serialize, TsType)]
+    #[serde(rename_all = "snake_case")]
+    enum Status {
+        NotFound,
+        #[serde(rename = "größe")]
+        Size,
+        #[serde(skip)]
+        Internal,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TsType)]
+    enum Nothing {
+        #[serde(skip)]
+        Hidden,
+    }
+
+    #[test]
+    fn derive_declarations() {
+        assert_eq!(
+            declaration::<User>(),
+            "export interface User {\n    \"userId\": number;\n    \"display \\\"name\\\"\": string;\n    \"friends\": (User)[];\n}"
+        );
+        assert_eq!(declaration::<Id>(), "export type UserId = number;");
+        assert_eq!(
+            declaration::<Status>(),
+            "export type Status = \"not_found\" | \"größe\";"
+        );
+        assert_eq!(declaration::<Nothing>(), "export type Nothing = never;");
+    }
+
+    #[test]
+    fn names_match_serde() {
+        macro_rules! check {
+            ($($rule:literal),*) => {$({
+                #[allow(dead_code)]
+                #[derive(serde::Serialize, TsType)]
+                #[serde(rename_all = $rule)]
+                struct Fields {
+                    user_id: u8,
+                }
+
+                #[derive(serde::Serialize, TsType)]
+                #[serde(rename_all = $rule)]
+                enum Variants {
+                    HttpError,
+                }
+
+                let json = serde_json::to_string(&Fields { user_id: 0 }).unwrap();
+                let field = json.split(':').next().unwrap().trim_start_matches('{');
+                assert!(declaration::<Fields>().contains(field), "{}: {}", $rule, json);
+                let json = serde_json::to_string(&Variants::HttpError).unwrap();
+                assert!(declaration::<Variants>().contains(&json), "{}: {}", $rule, json);
+            })*};
+        }
+        check!(
+            "lowercase",
+            "UPPERCASE",
+            "PascalCase",
+            "camelCase",
+            "snake_case",
+            "SCREAMING_SNAKE_CASE",
+            "kebab-case",
+            "SCREAMING-KEBAB-CASE"
+        );
+    }
+
+    #[crate::command(rename = "delete")]
+    fn delete_user(id: Id) -> Result<Status, String> {
+        Err(format!("can't delete {}", id.0))
+    }
+
+    #[test]
+    fn api_without_window() {
+        let ts = Api::new().command::<delete_user>().typescript();
+        assert!(ts.declarations.contains("export type UserId = number;"));
+        assert!(ts.client.contains(
+            "export const _delete = (arg0: T.UserId): Promise<T.Status> => invoke(\"delete\", arg0);"
+        ));
+    }
+
+    #[test]
+    fn api_is_dropped_with_window() {
+        let _mock = crate::mock::MockBackend::install();
+        let window = crate::Window::new();
+        let handle = window.handle();
+        window.bind_typed_command::<delete_user>().unwrap();
+        assert!(window.typescript().client.contains("_delete"));
+
+        drop(window);
+        assert!(!APIS.read().unwrap().contains_key(&handle));
+    }
+}
@@ -1,22 +1,49 @@
 use once_cell::sync::Lazy;
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
     ffi::{CStr, CString},
     fmt::Debug,
+    mem,
+    ops::{Deref, DerefMut},
+    panic::{self, AssertUnwindSafe},
     path::Path,
-    sync::RwLock,
+    str::Utf8Error,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
 };
 
-use webui_sys as ffi;
+use crate::{
+    backend::backend, file_handler, hooks, profile, state, ArgError, Error, IntoResponse, Result,
+};
+
+struct Callback {
+    window: usize,
+    generation: u64,
+    func: Arc<dyn Fn(&mut Event) + Send + Sync>,
+}
 
-type CallbackMap = HashMap<usize, Box<dyn Fn(&mut Event) + Send + Sync>>;
+type CallbackMap = HashMap<usize, Callback>;
 
 static EVENT_HANDLERS: Lazy<RwLock<CallbackMap>> = Lazy::new(|| RwLock::new(HashMap::new()));
 
-// type FileHandlerMap = HashMap<usize, Box<dyn Fn(&str) + Send + Sync>>;
-// static FILE_HANDLERS: Lazy<RwLock<FileHandlerMap>> = Lazy::new(|| RwLock::new(HashMap::new()));
+/// Distinguishes closures bound to the same bind id, since WebUI reuses the
+/// id when an element is bound again.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
 
-#[derive(Debug)]
+type PanicHandler = Arc<dyn Fn(&mut Event, &str) + Send + Sync>;
+
+static PANIC_HANDLER: Lazy<RwLock<Option<PanicHandler>>> = Lazy::new(|| RwLock::new(None));
+
+/// Set by [`clean`], after which windows must not be destroyed anymore.
+static CLEANED: AtomicBool = AtomicBool::new(false);
+
+/// Windows created through rust-webui that haven't been destroyed yet.
+static WINDOWS: Lazy<RwLock<BTreeSet<usize>>> = Lazy::new(|| RwLock::new(BTreeSet::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(usize)]
 pub enum Browser {
     NoBrowser = 0,
@@ -34,7 +61,7 @@ pub enum Browser {
     ChromiumBased,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(usize)]
 pub enum Runtime {
     None = 0,
@@ -42,16 +69,22 @@ pub enum Runtime {
     NodeJs,
 }
 
+/// An owned window, destroyed when dropped. Most methods are on
+/// [`WindowRef`], which `Window` derefs to.
 pub struct Window {
+    inner: WindowRef,
+}
+
+/// A window handle that doesn't own the window, e.g. [`Event::window`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowRef {
     handle: usize,
-    // file_handler: Option<Box<dyn Fn(&str)>>,
 }
 
 impl Debug for Window {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Window")
             .field("handle", &self.handle)
-            // .field("file_handler", &self.file_handler.is_some())
             .finish()
     }
 }
@@ -64,10 +97,66 @@ impl Default for Window {
 
 impl Window {
     pub fn new() -> Self {
-        let window_number = unsafe { ffi::webui_new_window() };
+        let window_number = backend().new_window();
+        Self::register(window_number)
+    }
+
+    pub fn with_number(window_number: usize) -> Self {
+        backend().new_window_id(window_number);
+        Self::register(window_number)
+    }
+
+    fn register(handle: usize) -> Self {
+        WINDOWS.write().unwrap().insert(handle);
         Self {
-            handle: window_number,
+            inner: WindowRef { handle },
+        }
+    }
+
+    /// Close the window, free its resources in WebUI and drop all closures
+    /// bound to it. This is the same as dropping the window.
+    pub fn destroy(self) {}
+
+    /// Give up ownership, so the window is not destroyed on drop.
+    pub fn leak(self) -> WindowRef {
+        let window = self.inner;
+        mem::forget(self);
+        window
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        if !CLEANED.load(Ordering::Acquire) {
+            backend().destroy(self.handle());
         }
+        self.unbind_all();
+        file_handler::remove_file_handler(self.handle());
+        state::remove_states(self.handle());
+        profile::remove_ephemeral(self.handle());
+        #[cfg(feature = "typescript")]
+        crate::ts::remove_api(self.handle());
+        WINDOWS.write().unwrap().remove(&self.handle());
+    }
+}
+
+impl Deref for Window {
+    type Target = WindowRef;
+
+    fn deref(&self) -> &WindowRef {
+        &self.inner
+    }
+}
+
+impl DerefMut for Window {
+    fn deref_mut(&mut self) -> &mut WindowRef {
+        &mut self.inner
+    }
+}
+
+impl WindowRef {
+    pub fn from_handle(handle: usize) -> Self {
+        Self { handle }
     }
 
     pub fn handle(&self) -> usize {
@@ -75,96 +164,219 @@ impl Window {
     }
 
     pub fn get_unique_window_id(&self) -> usize {
-        unsafe { ffi::webui_interface_get_window_id(self.handle()) }
+        backend().get_unique_window_id(self.handle())
     }
 
-    pub fn with_number(window_number: usize) -> Self {
-        unsafe {
-            ffi::webui_new_window_id(window_number);
-        }
-        Self {
-            handle: window_number,
-            // file_handler: None,
+    pub fn show(&self, content: &str) -> Result<()> {
+        let cstring = CString::new(content)?;
+        if backend().show(self.handle(), &cstring) {
+            Ok(())
+        } else {
+            Err(Error::ShowFailed)
         }
     }
 
-    pub fn show(&self, content: &str) -> bool {
-        let cstring = CString::new(content).unwrap();
-        unsafe { ffi::webui_show(self.handle(), cstring.as_ptr()) }
-    }
-
-    pub fn show_browser(&self, content: &str, browser: Browser) -> bool {
-        let cstring = CString::new(content).unwrap();
-        unsafe { ffi::webui_show_browser(self.handle(), cstring.as_ptr(), browser as usize) }
+    pub fn show_browser(&self, content: &str, browser: Browser) -> Result<()> {
+        let cstring = CString::new(content)?;
+        if backend().show_browser(self.handle(), &cstring, browser) {
+            Ok(())
+        } else {
+            Err(Error::BrowserNotFound(browser))
+        }
     }
 
     pub fn is_shown(&self) -> bool {
-        unsafe { ffi::webui_is_shown(self.handle()) }
+        backend().is_shown(self.handle())
     }
 
     pub fn set_size(&mut self, width: u32, height: u32) {
-        unsafe {
-            ffi::webui_set_size(self.handle(), width, height);
-        }
+        backend().set_size(self.handle(), width, height)
     }
 
     pub fn set_position(&mut self, x: u32, y: u32) {
-        unsafe {
-            ffi::webui_set_position(self.handle(), x, y);
-        }
+        backend().set_position(self.handle(), x, y)
     }
 
-    pub fn set_root_folder(&mut self, path: impl AsRef<Path>) -> bool {
-        let cstring = CString::new(path.as_ref().as_os_str().to_str().unwrap()).unwrap();
-        unsafe { ffi::webui_set_root_folder(self.handle(), cstring.as_ptr()) }
+    pub fn set_root_folder(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let cstring = path_to_cstring(path)?;
+        if backend().set_root_folder(self.handle(), &cstring) {
+            Ok(())
+        } else {
+            Err(Error::InvalidRootFolder(path.to_path_buf()))
+        }
     }
 
-    pub fn set_icon(&mut self, icon: &str, icon_type: &str) {
-        let icon_c = CString::new(icon).unwrap();
-        let icon_type_c = CString::new(icon_type).unwrap();
-        unsafe { ffi::webui_set_icon(self.handle(), icon_c.as_ptr(), icon_type_c.as_ptr()) }
+    pub fn set_icon(&mut self, icon: &str, icon_type: &str) -> Result<()> {
+        let icon_c = CString::new(icon)?;
+        let icon_type_c = CString::new(icon_type)?;
+        backend().set_icon(self.handle(), &icon_c, &icon_type_c);
+        Ok(())
     }
 
-    pub fn set_port(&mut self, port: usize) -> bool {
-        unsafe { ffi::webui_set_port(self.handle(), port) }
+    pub fn set_port(&mut self, port: usize) -> Result<()> {
+        if backend().set_port(self.handle(), port) {
+            Ok(())
+        } else {
+            Err(Error::PortInUse(port))
+        }
     }
 
     pub fn set_runtime(&mut self, runtime: Runtime) {
-        unsafe {
-            ffi::webui_set_runtime(self.handle(), runtime as usize);
-        }
+        backend().set_runtime(self.handle(), runtime)
     }
 
-    // pub fn set_file_handler()
+    /// Bind a closure to an element id or JS function name. Binding the same
+    /// element again replaces the previous closure.
+    ///
+    /// The value the closure returns becomes the response, see
+    /// [`IntoResponse`].
+    pub fn bind<R: IntoResponse>(
+        &self,
+        element: &str,
+        func: impl Fn(&mut Event) -> R + Send + Sync + 'static,
+    ) -> Result<Binding> {
+        let cstring = CString::new(element)?;
+
+        let bind_id = backend().bind(self.handle(), &cstring);
+        let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
 
-    pub fn bind(&self, element: &str, func: impl Fn(&mut Event) + Send + Sync + 'static) {
-        let cstring = CString::new(element).unwrap();
+        {
+            let mut cbs = EVENT_HANDLERS.write().unwrap();
+            cbs.insert(
+                bind_id,
+                Callback {
+                    window: self.handle(),
+                    generation,
+                    func: Arc::new(move |event: &mut Event| func(event).into_response(event)),
+                },
+            );
+        }
+        Ok(Binding {
+            window: self.handle(),
+            bind_id,
+            generation,
+        })
+    }
 
-        let bind_id = unsafe {
-            ffi::webui_interface_bind(self.handle(), cstring.as_ptr(), Some(event_handler))
+    /// Remove every closure bound to this window.
+    pub fn unbind_all(&self) {
+        let removed: Vec<_> = {
+            let mut cbs = EVENT_HANDLERS.write().unwrap();
+            let ids: Vec<_> = cbs
+                .iter()
+                .filter(|(_, cb)| cb.window == self.handle())
+                .map(|(&bind_id, _)| bind_id)
+                .collect();
+            ids.iter()
+                .filter_map(|bind_id| cbs.remove(bind_id))
+                .collect()
         };
+        // Dropped after unlocking, closures may own a window whose drop
+        // unbinds too.
+        drop(removed);
+        hooks::remove_hooks(self.handle());
+    }
 
-        {
-            let mut cbs = EVENT_HANDLERS.write().unwrap();
-            cbs.insert(bind_id, Box::new(func));
+    pub fn send_raw(&self, func: &str, buf: &[u8]) -> Result<()> {
+        let func_cstring = CString::new(func)?;
+        if !self.is_shown() {
+            return Err(Error::WindowNotShown);
         }
+
+        backend().send_raw(self.handle(), &func_cstring, buf);
+        Ok(())
+    }
+
+    /// Close the window. It can be shown again, use [`Window::destroy`] to
+    /// free it.
+    pub fn close(&self) {
+        backend().close(self.handle())
+    }
+
+    pub fn set_hide(&mut self, hide: bool) {
+        backend().set_hide(self.handle(), hide)
+    }
+
+    pub fn set_kiosk(&mut self, kiosk: bool) {
+        backend().set_kiosk(self.handle(), kiosk)
+    }
+
+    /// Allow the window to be reached from the public network.
+    pub fn set_public(&mut self, public: bool) {
+        backend().set_public(self.handle(), public)
     }
 
-    pub fn send_raw(&self, func: &str, buf: &[u8]) {
-        let func_cstring = CString::new(func).unwrap();
+    /// Use a browser profile, an empty `name` and `path` means the default
+    /// profile. Must be called before the window is shown, see also
+    /// [`use_profile`](WindowRef::use_profile).
+    pub fn set_profile(&mut self, name: &str, path: impl AsRef<Path>) -> Result<()> {
+        let name_c = CString::new(name)?;
+        let path_c = path_to_cstring(path.as_ref())?;
+        backend().set_profile(self.handle(), &name_c, &path_c);
+        Ok(())
+    }
 
-        unsafe {
-            ffi::webui_send_raw(
-                self.handle(),
-                func_cstring.as_ptr(),
-                buf.as_ptr() as *const _,
-                buf.len(),
-            )
+    pub fn navigate(&self, url: &str) -> Result<()> {
+        let cstring = CString::new(url)?;
+        if !self.is_shown() {
+            return Err(Error::WindowNotShown);
         }
+        backend().navigate(self.handle(), &cstring);
+        Ok(())
+    }
+
+    /// The full URL of the window, `None` before it is shown.
+    pub fn get_url(&self) -> Option<String> {
+        backend().get_url(self.handle())
     }
 }
 
+/// A closure bound with [`WindowRef::bind`].
+///
+/// Dropping a `Binding` keeps the closure bound, call [`Binding::unbind`] to
+/// remove it.
 #[derive(Debug)]
+pub struct Binding {
+    window: usize,
+    bind_id: usize,
+    generation: u64,
+}
+
+impl Binding {
+    pub fn bind_id(&self) -> usize {
+        self.bind_id
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Remove the closure. Returns `false` if it was already removed or
+    /// replaced by binding the same element again.
+    ///
+    /// WebUI has no way to unbind, so calls from the page after this get an
+    /// empty response.
+    pub fn unbind(self) -> bool {
+        let removed = {
+            let mut cbs = EVENT_HANDLERS.write().unwrap();
+            match cbs.get(&self.bind_id) {
+                Some(cb) if cb.generation == self.generation => cbs.remove(&self.bind_id),
+                _ => None,
+            }
+        };
+        removed.is_some()
+    }
+}
+
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    let s = path
+        .to_str()
+        .ok_or_else(|| Error::NonUtf8Path(path.to_path_buf()))?;
+    Ok(CString::new(s)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EventType {
     Disconnected = 0,
     Connected,
@@ -187,9 +399,12 @@ impl TryFrom<usize> for EventType {
     }
 }
 
+/// `WEBUI_MAX_ARG + 1`, the number of arguments WebUI keeps per call.
+const MAX_ARGS: usize = 17;
+
 #[derive(Debug)]
 pub struct Event<'a> {
-    pub window: Window,
+    pub window: WindowRef,
     pub event_type: EventType,
     pub element: &'a str,
     pub event_number: usize,
@@ -198,92 +413,267 @@ pub struct Event<'a> {
 
 impl<'a> Event<'a> {
     pub fn get_int_at(&self, index: usize) -> i64 {
-        unsafe { ffi::webui_interface_get_int_at(self.window.handle(), self.event_number, index) }
+        backend().int_at(self.window.handle(), self.event_number, index)
     }
 
     pub fn get_bool_at(&self, index: usize) -> bool {
-        unsafe { ffi::webui_interface_get_bool_at(self.window.handle(), self.event_number, index) }
+        backend().bool_at(self.window.handle(), self.event_number, index)
     }
 
-    pub fn get_string_at(&self, index: usize) -> &str {
-        unsafe {
-            let ptr =
-                ffi::webui_interface_get_string_at(self.window.handle(), self.event_number, index);
-            let length =
-                ffi::webui_interface_get_size_at(self.window.handle(), self.event_number, index);
-            let s = std::slice::from_raw_parts(ptr as *const u8, length);
-            std::str::from_utf8_unchecked(s)
-        }
+    /// The argument as text, invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn get_string_at(&self, index: usize) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.get_bytes_at(index))
     }
 
-    pub fn set_response(&mut self, response: &str) {
-        let cstring = CString::new(response).unwrap();
-        unsafe {
-            ffi::webui_interface_set_response(
-                self.window.handle(),
-                self.event_number,
-                cstring.as_ptr(),
-            );
+    pub fn get_str_at(&self, index: usize) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(self.get_bytes_at(index))
+    }
+
+    /// The raw bytes of the argument, e.g. of a `Uint8Array`. Missing
+    /// arguments are empty.
+    pub fn get_bytes_at(&self, index: usize) -> &[u8] {
+        if index >= MAX_ARGS {
+            return &[];
+        }
+        let (ptr, length) = backend().arg_at(self.window.handle(), self.event_number, index);
+        if ptr.is_null() {
+            return &[];
         }
+        // The backend keeps the argument alive while the event is handled.
+        unsafe { std::slice::from_raw_parts(ptr, length) }
+    }
+
+    pub fn get_f64_at(&self, index: usize) -> Result<f64, ArgError> {
+        self.arg(index)
+    }
+
+    /// The number of arguments passed to `webui.call`. WebUI doesn't send
+    /// the count, so trailing empty strings are not counted.
+    pub fn arg_count(&self) -> usize {
+        (0..MAX_ARGS)
+            .rev()
+            .find(|&index| !self.get_bytes_at(index).is_empty())
+            .map_or(0, |index| index + 1)
+    }
+
+    /// Respond with a string, which must not contain NUL.
+    pub fn set_response(&mut self, response: &str) -> Result<()> {
+        self.return_bytes(response.as_bytes())
+    }
+
+    pub fn return_int(&mut self, n: i64) {
+        backend().return_int(self.window.handle(), self.event_number, n)
+    }
+
+    pub fn return_bool(&mut self, b: bool) {
+        backend().return_bool(self.window.handle(), self.event_number, b)
+    }
+
+    /// Respond with raw bytes. WebUI responses are C strings, so the bytes
+    /// must not contain NUL.
+    pub fn return_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let cstring = CString::new(bytes)?;
+        self.set_cstr_response(&cstring);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn return_json<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let json = serde_json::to_vec(value).map_err(Error::Json)?;
+        self.return_bytes(&json)
     }
 
     pub fn set_cstr_response(&mut self, response: &std::ffi::CStr) {
-        unsafe {
-            ffi::webui_interface_set_response(
-                self.window.handle(),
-                self.event_number,
-                response.as_ptr(),
-            );
+        backend().set_response(self.window.handle(), self.event_number, response);
+    }
+}
+
+/// Set what happens when a bound closure panics, the closure receives the
+/// event and the panic message.
+///
+/// By default the response is set to the error envelope of the `json` module,
+/// `{"t":"Err","c":{"kind":"panic","message":"..."}}`, so typed JS clients
+/// reject the promise. Panics are only caught with `panic = "unwind"`.
+pub fn set_panic_handler(handler: impl Fn(&mut Event, &str) + Send + Sync + 'static) {
+    *PANIC_HANDLER.write().unwrap() = Some(Arc::new(handler));
+}
+
+fn default_panic_handler(event: &mut Event, message: &str) {
+    // `json_string` escapes NUL, so this can't fail.
+    let _ = event.set_response(&format!(
+        r#"{{"t":"Err","c":{{"kind":"panic","message":{}}}}}"#,
+        json_string(message)
+    ));
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
         }
     }
+    out.push('"');
+    out
 }
 
-unsafe extern "C" fn event_handler(
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+pub(crate) unsafe extern "C" fn event_handler(
     window_number: usize,
     event_type: usize,
     element_ptr: *mut std::os::raw::c_char,
     event_number: usize,
     bind_id: usize,
 ) {
-    let window = Window {
-        handle: window_number,
+    let Ok(event_type) = EventType::try_from(event_type) else {
+        log::warn!("ignoring event with unknown type {}", event_type);
+        return;
     };
-    let element = CStr::from_ptr(element_ptr)
-        .to_str()
-        .expect("element name is not valid utf8");
+    let Ok(element) = CStr::from_ptr(element_ptr).to_str() else {
+        log::warn!("ignoring event for element with a non-utf8 name");
+        return;
+    };
+    // Don't hold the lock while the closure runs, so it can bind and unbind.
+    let func = {
+        let cbs = EVENT_HANDLERS.read().unwrap_or_else(|err| err.into_inner());
+        cbs.get(&bind_id).map(|cb| cb.func.clone())
+    };
+
     let mut event = Event {
-        window,
-        event_type: event_type.try_into().unwrap(),
+        window: WindowRef::from_handle(window_number),
+        event_type,
         element,
         event_number,
         bind_id,
     };
-    {
-        let cbs = EVENT_HANDLERS.read().unwrap();
-        cbs[&bind_id](&mut event);
+    if let Some(func) = &func {
+        call(&mut event, &**func);
+    }
+    // Hooks run whatever is bound, so binding `""` doesn't disable them.
+    let hooked = hooks::dispatch(&mut event);
+    if func.is_none() && !hooked {
+        log::warn!(
+            "ignoring event for unknown bind id {} ({:?})",
+            bind_id,
+            element
+        );
+    }
+}
+
+fn call(event: &mut Event, func: &(dyn Fn(&mut Event) + Send + Sync)) {
+    let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| func(event))) else {
+        return;
+    };
+
+    let message = panic_message(payload.as_ref());
+    log::error!("closure bound to {:?} panicked: {}", event.element, message);
+    let handler = PANIC_HANDLER
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match handler {
+        Some(handler) => handler(event, message),
+        None => default_panic_handler(event, message),
+    }));
+    if result.is_err() {
+        log::error!("panic handler panicked");
     }
 }
 
 pub fn wait() {
-    unsafe { ffi::webui_wait() }
+    backend().wait()
 }
 
+/// Free all WebUI resources, drop every bound closure and file handler and
+/// delete ephemeral profiles.
 pub fn clean() {
-    unsafe { ffi::webui_clean() }
+    backend().clean();
+    CLEANED.store(true, Ordering::Release);
+    let handlers = mem::take(&mut *EVENT_HANDLERS.write().unwrap());
+    drop(handlers);
+    hooks::remove_all_hooks();
+    file_handler::remove_all_file_handlers();
+    state::remove_all_states();
+    profile::remove_all_ephemeral();
+    #[cfg(feature = "typescript")]
+    crate::ts::remove_all_apis();
+    WINDOWS.write().unwrap().clear();
+}
+
+/// All windows created through rust-webui that haven't been destroyed.
+pub(crate) fn live_windows() -> Vec<WindowRef> {
+    WINDOWS
+        .read()
+        .unwrap()
+        .iter()
+        .map(|&handle| WindowRef { handle })
+        .collect()
+}
+
+/// Whether the window was created through rust-webui and not destroyed.
+pub(crate) fn is_live(handle: usize) -> bool {
+    WINDOWS.read().unwrap().contains(&handle)
+}
+
+/// Serve every window over HTTPS with the given PEM encoded certificate and
+/// private key. Call it before showing the first window.
+#[cfg(feature = "tls")]
+pub fn set_tls_certificate(certificate_pem: &str, private_key_pem: &str) -> Result<()> {
+    let certificate_pem = CString::new(certificate_pem)?;
+    let private_key_pem = CString::new(private_key_pem)?;
+    if backend().set_tls_certificate(&certificate_pem, &private_key_pem) {
+        Ok(())
+    } else {
+        Err(Error::InvalidCertificate)
+    }
+}
+
+/// Serve every window over HTTPS with a certificate generated by WebUI.
+/// Browsers will warn about it, so it is meant for local use only.
+#[cfg(feature = "tls")]
+pub fn set_self_signed_tls() -> Result<()> {
+    set_tls_certificate("", "")
+}
+
+/// Set the root folder of every window that doesn't have its own.
+pub fn set_default_root_folder(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let cstring = path_to_cstring(path)?;
+    if backend().set_default_root_folder(&cstring) {
+        Ok(())
+    } else {
+        Err(Error::InvalidRootFolder(path.to_path_buf()))
+    }
 }
 
 pub fn is_app_running() -> bool {
-    unsafe { ffi::webui_interface_is_app_running() }
+    backend().is_app_running()
 }
 
 pub fn set_timeout(second: usize) {
-    unsafe { ffi::webui_set_timeout(second) }
+    backend().set_timeout(second)
 }
 
 pub fn get_new_window_id() -> usize {
-    unsafe { ffi::webui_get_new_window_id() }
+    backend().get_new_window_id()
 }
 
 pub fn exit() {
-    unsafe { ffi::webui_exit() }
+    backend().exit()
 }
@@ -0,0 +1,37 @@
+//! Drives a real window with the test client, in its own process so no test
+//! installs the mock backend.
+
+#![cfg(feature = "test-client")]
+
+use rust_webui::{
+    test_client::{Message, TestClient},
+    Browser, Window,
+};
+
+#[test]
+#[ignore = "needs a real WebUI library, run with WEBUI_LIB_DIR pointing to one"]
+fn call_and_run_without_browser() {
+    let window = Window::new();
+    window
+        .bind("add", |event| event.get_int_at(0) + event.get_int_at(1))
+        .unwrap();
+    window
+        .show_browser(
+            "<html><script src=\"webui.js\"></script></html>",
+            Browser::NoBrowser,
+        )
+        .unwrap();
+
+    let mut client = TestClient::connect(&window).unwrap();
+    assert_eq!(client.call("add", &["1", "2"]).unwrap(), "3");
+
+    window.run("console.log(1)").unwrap();
+    assert_eq!(
+        client.recv().unwrap(),
+        Message::Run("console.log(1)".to_string())
+    );
+
+    client.close().unwrap();
+    drop(window);
+    rust_webui::clean();
+}
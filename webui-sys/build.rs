@@ -1,18 +1,223 @@
-// const WEBUI_VERSION: &str = "2.4.2";
-// const WEBUI_RELEASE_BASE_URL: &str = "https://github.com/webui-dev/webui/releases/download";
+//! Links a prebuilt `webui-2-static`, from `WEBUI_LIB_DIR` or the release
+//! archive unpacked next to this crate, e.g. `webui-linux-clang-x64/`.
+//!
+//! With the `from-source` feature WebUI is compiled from a checkout of
+//! <https://github.com/webui-dev/webui> at the tag in `WEBUI_VERSION`, found in
+//! `WEBUI_SRC_DIR` or `webui/` next to this crate.
+//!
+//! With the `bindgen` feature the bindings are regenerated from the
+//! `webui.h` of the linked library. Either way the build fails if the library
+//! comes without that header or it is for a different WebUI version than the
+//! bindings.
+//!
+//! With the `tls` feature the secure variant is linked, or built against the
+//! system OpenSSL, `OPENSSL_DIR` points to a custom installation. WebUI
+//! uses the OpenSSL 1.1 API, so OpenSSL 1.1.1 or 3.x is required.
+//! `.github/workflows/ci.yml` builds and tests this configuration.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// The WebUI version `src/bindings.rs` was generated from.
+const WEBUI_VERSION: &str = "2.4.2";
 
 fn main() {
-    link_webui();
+    println!("cargo:rerun-if-env-changed=WEBUI_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=WEBUI_SRC_DIR");
+    println!("cargo:rerun-if-env-changed=OPENSSL_DIR");
+
+    // The header that belongs to the linked library, `None` if there is no
+    // library to link.
+    let lib_header = if let Some(dir) = env::var_os("WEBUI_LIB_DIR") {
+        // Relative paths would resolve differently for the linker, which runs
+        // in the workspace root.
+        let dir = fs::canonicalize(&dir)
+            .unwrap_or_else(|err| panic!("WEBUI_LIB_DIR {}: {}", Path::new(&dir).display(), err));
+        link_prebuilt(&dir);
+        Some(require_header(&dir))
+    } else if cfg!(feature = "from-source") {
+        let src = env::var_os("WEBUI_SRC_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("webui"));
+        if !src.join("src/webui.c").is_file() {
+            panic!(
+                "WebUI {version} sources not found in {src}. Check out \
+                 https://github.com/webui-dev/webui at tag {version} there, or \
+                 point WEBUI_SRC_DIR to such a checkout",
+                version = WEBUI_VERSION,
+                src = src.display(),
+            );
+        }
+        build_webui(&src);
+        Some(require_header(&src))
+    } else {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(prebuilt_dir());
+        if dir.is_dir() {
+            link_prebuilt(&dir);
+            Some(require_header(&dir))
+        } else {
+            // This crate still builds, binaries fail to link.
+            println!(
+                "cargo:warning=no WebUI library in {}, set WEBUI_LIB_DIR or \
+                 enable the `from-source` feature to link",
+                dir.display()
+            );
+            None
+        }
+    };
+
+    let bindings_version = match &lib_header {
+        Some(header) => {
+            let bindings_version = bindings(header);
+            let lib_version = header_version(header);
+            if lib_version != bindings_version {
+                panic!(
+                    "the bindings are for WebUI {} but the linked library is WebUI {}, \
+                     enable the `bindgen` feature or link a matching library",
+                    bindings_version, lib_version
+                );
+            }
+            bindings_version
+        }
+        None if cfg!(feature = "bindgen") => {
+            panic!("the `bindgen` feature needs a WebUI library with its webui.h")
+        }
+        None => WEBUI_VERSION.to_string(),
+    };
+    println!("cargo:rustc-env=WEBUI_VERSION={}", bindings_version);
+}
+
+/// Generate the bindings from `header`, returns the WebUI version they are for.
+#[cfg(feature = "bindgen")]
+fn bindings(header: &Path) -> String {
+    let out = Path::new(&env::var_os("OUT_DIR").unwrap()).join("bindings.rs");
+    bindgen::Builder::default()
+        .header(header.to_str().expect("webui.h path is not valid utf8"))
+        .allowlist_function("webui_.*")
+        .allowlist_type("webui_event_t")
+        .layout_tests(true)
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("failed to generate bindings")
+        .write_to_file(&out)
+        .expect("failed to write bindings");
+    header_version(header)
 }
 
-#[cfg(target_os = "macos")]
-fn link_webui() {
-    println!("cargo:rustc-link-search=webui-macos-clang-arm64/");
-    println!("cargo:rustc-link-lib=static=webui-2-static");
+#[cfg(not(feature = "bindgen"))]
+fn bindings(header: &Path) -> String {
+    println!("cargo:rerun-if-changed={}", header.display());
+    WEBUI_VERSION.to_string()
 }
 
-#[cfg(target_os = "linux")]
-fn link_webui() {
-    println!("cargo:rustc-link-search=webui-linux-clang-x64/");
-    println!("cargo:rustc-link-lib=static=webui-2-static");
+fn require_header(dir: &Path) -> PathBuf {
+    [dir.join("webui.h"), dir.join("include/webui.h")]
+        .into_iter()
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| {
+            panic!(
+                "cannot find webui.h for the linked library, put the header of \
+                 the WebUI version in {0} or {0}/include",
+                dir.display()
+            )
+        })
+}
+
+/// Read `#define WEBUI_VERSION "x.y.z"` from a WebUI header.
+fn header_version(header: &Path) -> String {
+    let contents = fs::read_to_string(header)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", header.display(), err));
+    contents
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#define"))
+        .filter_map(|line| line.trim().strip_prefix("WEBUI_VERSION"))
+        .find_map(|value| value.trim().strip_prefix('"')?.split('"').next())
+        .unwrap_or_else(|| panic!("no WEBUI_VERSION in {}", header.display()))
+        .to_string()
+}
+
+fn build_webui(src: &Path) {
+    println!("cargo:rerun-if-changed={}", src.join("src").display());
+    println!("cargo:rerun-if-changed={}", src.join("include").display());
+
+    let mut build = cc::Build::new();
+    if cfg!(feature = "clang") {
+        build.compiler("clang");
+    }
+    build
+        .file(src.join("src/civetweb/civetweb.c"))
+        .file(src.join("src/webui.c"))
+        .include(src.join("include"))
+        .include(src.join("src/civetweb"))
+        .define("NDEBUG", None)
+        .define("NO_CACHING", None)
+        .define("NO_CGI", None)
+        .define("USE_WEBSOCKET", None)
+        .warnings(false);
+
+    if cfg!(feature = "tls") {
+        build
+            .define("WEBUI_TLS", None)
+            .define("NO_SSL_DL", None)
+            .define("OPENSSL_API_1_1", None);
+        if let Some(dir) = env::var_os("OPENSSL_DIR") {
+            build.include(Path::new(&dir).join("include"));
+        }
+    } else {
+        build.define("NO_SSL", None);
+    }
+
+    build.compile(lib_name());
+
+    link_system_libs();
+}
+
+fn link_prebuilt(dir: &Path) {
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib=static={}", lib_name());
+    link_system_libs();
+}
+
+fn lib_name() -> &'static str {
+    if cfg!(feature = "tls") {
+        "webui-2-secure-static"
+    } else {
+        "webui-2-static"
+    }
+}
+
+fn prebuilt_dir() -> &'static str {
+    match env::var("CARGO_CFG_TARGET_OS").as_deref() {
+        Ok("macos") => "webui-macos-clang-arm64",
+        _ => "webui-linux-clang-x64",
+    }
+}
+
+fn link_system_libs() {
+    if cfg!(feature = "tls") {
+        if let Some(dir) = env::var_os("OPENSSL_DIR") {
+            println!(
+                "cargo:rustc-link-search=native={}",
+                Path::new(&dir).join("lib").display()
+            );
+        }
+        let (ssl, crypto) = match env::var("CARGO_CFG_TARGET_OS").as_deref() {
+            Ok("windows") => ("libssl", "libcrypto"),
+            _ => ("ssl", "crypto"),
+        };
+        println!("cargo:rustc-link-lib={}", ssl);
+        println!("cargo:rustc-link-lib={}", crypto);
+    }
+
+    match env::var("CARGO_CFG_TARGET_OS").as_deref() {
+        Ok("windows") => {
+            for lib in ["ws2_32", "user32", "advapi32", "shell32", "ole32"] {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+        }
+        Ok("macos") => {}
+        _ => println!("cargo:rustc-link-lib=pthread"),
+    }
 }
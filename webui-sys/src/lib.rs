@@ -1,9 +1,20 @@
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(not(feature = "bindgen"))]
 include!("./bindings.rs");
 
+/// The WebUI version the bindings were generated for.
+pub const WEBUI_VERSION: &str = env!("WEBUI_VERSION");
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {}
+    fn event_layout() {
+        // Five pointer sized fields, on every target.
+        let word = std::mem::size_of::<usize>();
+        assert_eq!(std::mem::size_of::<webui_event_t>(), 5 * word);
+        assert_eq!(std::mem::align_of::<webui_event_t>(), word);
+    }
 }